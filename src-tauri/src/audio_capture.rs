@@ -1,4 +1,6 @@
+use crate::transcription::Transcriber;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::thread;
 use std::sync::mpsc::{channel, Sender};
 
@@ -16,45 +18,155 @@ lazy_static::lazy_static! {
     }));
 }
 
-#[cfg(windows)]
+/// Current VU meter reading, published by the capture consumer loop and read by
+/// `get_audio_levels()`. Stored as raw `f32` bit patterns in atomics rather than
+/// behind a `Mutex` so a UI polling the meter never blocks (or is blocked by) the
+/// capture callback/consumer thread.
+static AUDIO_LEVEL_RMS_BITS: AtomicU32 = AtomicU32::new(0);
+static AUDIO_LEVEL_PEAK_BITS: AtomicU32 = AtomicU32::new(0);
+
+/// Compute RMS and peak amplitude for a block of `f32` samples and publish them to
+/// the atomic pair backing `get_audio_levels()`. Peak is a simple peak-hold: a louder
+/// block raises it at once, and every quieter block decays the held value by 5%.
+fn publish_audio_levels(samples: &[f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    let sum_sq: f32 = samples.iter().map(|&s| s * s).sum();
+    let rms = (sum_sq / samples.len() as f32).sqrt();
+    let peak = samples.iter().fold(0.0f32, |acc, &s| acc.max(s.abs()));
+
+    AUDIO_LEVEL_RMS_BITS.store(rms.to_bits(), Ordering::Relaxed);
+
+    let prev_peak = f32::from_bits(AUDIO_LEVEL_PEAK_BITS.load(Ordering::Relaxed));
+    if peak >= prev_peak {
+        AUDIO_LEVEL_PEAK_BITS.store(peak.to_bits(), Ordering::Relaxed);
+    } else {
+        // Slow peak-hold decay so the meter doesn't flicker down to 0 between blocks.
+        let decayed = prev_peak * 0.95;
+        AUDIO_LEVEL_PEAK_BITS.store(decayed.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Current audio level, read without blocking the capture thread. `rms` is
+/// directly convertible to dBFS via `20.0 * rms.log10()`.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct AudioLevels {
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// Read the most recently published RMS/peak levels from the active capture session.
+pub fn get_audio_levels() -> AudioLevels {
+    AudioLevels {
+        rms: f32::from_bits(AUDIO_LEVEL_RMS_BITS.load(Ordering::Relaxed)),
+        peak: f32::from_bits(AUDIO_LEVEL_PEAK_BITS.load(Ordering::Relaxed)),
+    }
+}
+
+/// A fixed-duration slice of captured audio, timestamped relative to `audio_start_time`.
+struct CaptionChunk {
+    start_secs: f64,
+    end_secs: f64,
+    pcm: Vec<f32>,
+    sample_rate: u32,
+}
+
+struct CaptionState {
+    is_capturing: bool,
+    stop_signal: Option<Sender<()>>,
+    capture_thread: Option<thread::JoinHandle<()>>,
+    transcribe_thread: Option<thread::JoinHandle<Vec<(f64, f64, String)>>>,
+}
+
+lazy_static::lazy_static! {
+    static ref CAPTION_STATE: Arc<Mutex<CaptionState>> = Arc::new(Mutex::new(CaptionState {
+        is_capturing: false,
+        stop_signal: None,
+        capture_thread: None,
+        transcribe_thread: None,
+    }));
+}
+
+/// Resolve a "loopback" capture device for the current platform. Windows' WASAPI
+/// exposes the output device directly as an input-capable loopback; Linux's
+/// PulseAudio/PipeWire stack exposes the same idea as a `.monitor` input device
+/// alongside real microphones; macOS has no OS-level loopback, so we look for a
+/// commonly-used aggregate/loopback device (e.g. BlackHole, Soundflower) by name
+/// and fall back to the default input device if none is installed.
+fn resolve_loopback_device(host: &cpal::Host) -> Option<cpal::Device> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    #[cfg(target_os = "windows")]
+    {
+        host.default_output_device()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        host.input_devices().ok().and_then(|mut devices| {
+            devices.find(|d| d.name().map(|n| n.contains(".monitor")).unwrap_or(false))
+        }).or_else(|| host.default_input_device())
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        const LOOPBACK_NAMES: [&str; 3] = ["BlackHole", "Soundflower", "Loopback Audio"];
+        host.input_devices().ok().and_then(|mut devices| {
+            devices.find(|d| {
+                d.name().map(|n| LOOPBACK_NAMES.iter().any(|candidate| n.contains(candidate)))
+                    .unwrap_or(false)
+            })
+        }).or_else(|| host.default_input_device())
+    }
+}
+
 pub fn start_audio_capture(output_path: String) -> Result<(), String> {
     use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
     use hound::{WavWriter, WavSpec};
     use std::sync::mpsc::Receiver;
-    
+
     let mut state = AUDIO_STATE.lock().unwrap();
-    
+
     if state.is_recording {
         return Err("Audio capture already in progress".to_string());
     }
-    
+
     let (stop_tx, stop_rx): (Sender<()>, Receiver<()>) = channel();
-    
+
     // Spawn thread to handle the audio stream
     let recording_thread = thread::spawn(move || {
         println!("Audio capture thread started");
-        
-        // Get the default output device (what's playing to speakers)
+
+        // Resolve the platform-appropriate loopback/system-audio device
         let host = cpal::default_host();
-        let device = match host.default_output_device() {
+        let device = match resolve_loopback_device(&host) {
             Some(d) => d,
             None => {
-                eprintln!("No default output device available");
+                eprintln!("No loopback or input device available");
                 return;
             }
         };
-        
+
         println!("Using audio device: {}", device.name().unwrap_or_else(|_| "Unknown".to_string()));
         
-        // Get the default output config
-        let config = match device.default_output_config() {
+        // On Windows the device is the speaker output, opened in WASAPI loopback mode
+        // via its *output* config. On Linux/macOS the resolved device is already an
+        // input (a monitor source or loopback aggregate device), so its input config
+        // is what `build_input_stream` expects.
+        #[cfg(target_os = "windows")]
+        let config_result = device.default_output_config();
+        #[cfg(not(target_os = "windows"))]
+        let config_result = device.default_input_config();
+
+        let config = match config_result {
             Ok(c) => c,
             Err(e) => {
-                eprintln!("Failed to get default output config: {}", e);
+                eprintln!("Failed to get default device config: {}", e);
                 return;
             }
         };
-        
+
         println!("Audio config: {:?}", config);
         
         let sample_rate = config.sample_rate().0;
@@ -166,6 +278,10 @@ pub fn start_audio_capture(output_path: String) -> Result<(), String> {
             // Receive audio data with timeout
             match audio_rx.recv_timeout(std::time::Duration::from_millis(100)) {
                 Ok(samples) => {
+                    // Compute levels here in the consumer loop, not the cpal callback,
+                    // so metering never risks glitching the audio stream.
+                    publish_audio_levels(&samples);
+
                     // Write actual audio samples
                     for sample in samples {
                         let sample_i16 = (sample * i16::MAX as f32) as i16;
@@ -209,11 +325,6 @@ pub fn start_audio_capture(output_path: String) -> Result<(), String> {
     Ok(())
 }
 
-#[cfg(not(windows))]
-pub fn start_audio_capture(_output_path: String) -> Result<(), String> {
-    Err("WASAPI audio capture is only supported on Windows".to_string())
-}
-
 pub fn stop_audio_capture() -> Result<(), String> {
     let mut state = AUDIO_STATE.lock().unwrap();
     
@@ -240,6 +351,9 @@ pub fn stop_audio_capture() -> Result<(), String> {
         state.is_recording = false;
     }
     
+    AUDIO_LEVEL_RMS_BITS.store(0, Ordering::Relaxed);
+    AUDIO_LEVEL_PEAK_BITS.store(0, Ordering::Relaxed);
+
     println!("Audio capture stopped");
     Ok(())
 }
@@ -248,3 +362,823 @@ pub fn is_audio_capturing() -> bool {
     let state = AUDIO_STATE.lock().unwrap();
     state.is_recording
 }
+
+/// Opus only accepts a handful of sample rates; pick whichever supported rate is
+/// closest to the device's native rate.
+fn nearest_opus_sample_rate(rate: u32) -> u32 {
+    const SUPPORTED: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+    *SUPPORTED.iter().min_by_key(|&&r| (r as i64 - rate as i64).abs()).unwrap()
+}
+
+fn build_opus_head(channels: u8, sample_rate: u32) -> Vec<u8> {
+    let mut head = Vec::new();
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(channels);
+    head.extend_from_slice(&0u16.to_le_bytes()); // pre-skip
+    head.extend_from_slice(&sample_rate.to_le_bytes()); // original input sample rate, for reference
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family (0 = mono/stereo)
+    head
+}
+
+fn build_opus_tags() -> Vec<u8> {
+    let mut tags = Vec::new();
+    tags.extend_from_slice(b"OpusTags");
+    let vendor = b"vibe-clips";
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+    tags
+}
+
+/// Capture loopback audio and encode it directly to Opus-in-Ogg instead of
+/// uncompressed PCM WAV, for long clips where `hound`'s raw 16-bit output gets huge.
+/// Opus requires fixed 20ms frames, so incoming samples are buffered until a full
+/// frame is available; any trailing partial frame on stop is zero-padded and flushed.
+pub fn start_audio_capture_opus(output_path: String, bitrate: i32) -> Result<(), String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+    use opus::{Application, Channels, Encoder};
+
+    let mut state = AUDIO_STATE.lock().unwrap();
+    if state.is_recording {
+        return Err("Audio capture already in progress".to_string());
+    }
+
+    let (stop_tx, stop_rx) = channel::<()>();
+
+    let recording_thread = thread::spawn(move || {
+        let host = cpal::default_host();
+        let device = match resolve_loopback_device(&host) {
+            Some(d) => d,
+            None => {
+                eprintln!("No loopback or input device available");
+                return;
+            }
+        };
+
+        #[cfg(target_os = "windows")]
+        let config_result = device.default_output_config();
+        #[cfg(not(target_os = "windows"))]
+        let config_result = device.default_input_config();
+
+        let config = match config_result {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Failed to get device config: {}", e);
+                return;
+            }
+        };
+
+        let native_rate = config.sample_rate().0;
+        let device_channels = config.channels().max(1) as usize;
+        // Opus only speaks mono or stereo without multi-channel mapping tables; remix
+        // any wider device layout (5.1, etc.) down to stereo before it ever reaches the
+        // encoder, so `channels` below always matches what's actually buffered/encoded.
+        let channels = device_channels.min(2);
+        let opus_rate = nearest_opus_sample_rate(native_rate);
+        let frame_size = opus_rate as usize / 50; // 20ms frame, per Opus's fixed-frame requirement
+
+        let (audio_tx, audio_rx) = channel::<Vec<f32>>();
+        let stream = device.build_input_stream(
+            &config.clone().into(),
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let _ = audio_tx.send(data.to_vec());
+            },
+            move |err| eprintln!("Opus capture stream error: {}", err),
+            None,
+        );
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Failed to build Opus capture stream: {}", e);
+                return;
+            }
+        };
+        if stream.play().is_err() {
+            eprintln!("Failed to start Opus capture stream");
+            return;
+        }
+
+        let opus_channels = if channels >= 2 { Channels::Stereo } else { Channels::Mono };
+        let mut encoder = match Encoder::new(opus_rate, opus_channels, Application::Audio) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Failed to create Opus encoder: {}", e);
+                return;
+            }
+        };
+        let _ = encoder.set_bitrate(opus::Bitrate::Bits(bitrate));
+
+        let file = match std::fs::File::create(&output_path) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("Failed to create Opus output file: {}", e);
+                return;
+            }
+        };
+        let mut packet_writer = PacketWriter::new(file);
+        let serial: u32 = 1;
+
+        let _ = packet_writer.write_packet(
+            build_opus_head(channels as u8, native_rate),
+            serial,
+            PacketWriteEndInfo::EndPage,
+            0,
+        );
+        let _ = packet_writer.write_packet(
+            build_opus_tags(),
+            serial,
+            PacketWriteEndInfo::EndPage,
+            0,
+        );
+
+        let resample_needed = opus_rate != native_rate;
+        let mut buffer: Vec<f32> = Vec::new();
+        let mut granule_pos: u64 = 0;
+        let mut encode_buf = vec![0u8; 4000];
+
+        loop {
+            match stop_rx.try_recv() {
+                Ok(_) | Err(std::sync::mpsc::TryRecvError::Disconnected) => break,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            }
+
+            match audio_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+                Ok(samples) => {
+                    let samples = remix_channels(&samples, device_channels, channels);
+                    let samples = if resample_needed {
+                        resample_linear(&samples, channels, native_rate, opus_rate)
+                    } else {
+                        samples
+                    };
+                    buffer.extend(samples);
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            let samples_per_frame = frame_size * channels;
+            while buffer.len() >= samples_per_frame {
+                let frame: Vec<f32> = buffer.drain(0..samples_per_frame).collect();
+                match encoder.encode_float(&frame, &mut encode_buf) {
+                    Ok(len) => {
+                        granule_pos += frame_size as u64;
+                        let _ = packet_writer.write_packet(
+                            encode_buf[..len].to_vec(),
+                            serial,
+                            PacketWriteEndInfo::NormalPacket,
+                            granule_pos,
+                        );
+                    }
+                    Err(e) => eprintln!("Opus encode error: {}", e),
+                }
+            }
+        }
+
+        // Flush a trailing partial frame, zero-padded to the fixed Opus frame size.
+        if !buffer.is_empty() {
+            let samples_per_frame = frame_size * channels;
+            buffer.resize(samples_per_frame, 0.0);
+            if let Ok(len) = encoder.encode_float(&buffer, &mut encode_buf) {
+                granule_pos += frame_size as u64;
+                let _ = packet_writer.write_packet(
+                    encode_buf[..len].to_vec(),
+                    serial,
+                    PacketWriteEndInfo::EndStream,
+                    granule_pos,
+                );
+            }
+        }
+
+        drop(stream);
+    });
+
+    state.is_recording = true;
+    state.stop_signal = Some(stop_tx);
+    state.recording_thread = Some(recording_thread);
+
+    Ok(())
+}
+
+/// Linearly resample interleaved `f32` samples from `from_rate` to `to_rate`,
+/// preserving channel layout. A no-op if the rates already match.
+fn resample_linear(samples: &[f32], channels: usize, from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || channels == 0 {
+        return samples.to_vec();
+    }
+
+    let frame_count = samples.len() / channels;
+    if frame_count == 0 {
+        return Vec::new();
+    }
+
+    let ratio = to_rate as f64 / from_rate as f64;
+    let out_frames = ((frame_count as f64) * ratio).round() as usize;
+    let mut out = Vec::with_capacity(out_frames * channels);
+
+    for out_frame in 0..out_frames {
+        let src_pos = out_frame as f64 / ratio;
+        let src_index = src_pos.floor() as usize;
+        let frac = (src_pos - src_index as f64) as f32;
+        let next_index = (src_index + 1).min(frame_count - 1);
+
+        for ch in 0..channels {
+            let a = samples[src_index.min(frame_count - 1) * channels + ch];
+            let b = samples[next_index * channels + ch];
+            out.push(a + (b - a) * frac);
+        }
+    }
+
+    out
+}
+
+/// Convert interleaved `f32` samples from `from_channels` to `to_channels` so two
+/// streams captured with different channel counts (e.g. a mono mic feeding a
+/// stereo system-audio mix) can be mixed sample-for-sample instead of silently
+/// misaligning - `resample_linear` above only fixes sample *rate*, not layout.
+/// Collapsing to mono averages all source channels together; expanding from
+/// mono duplicates the single channel into every output channel; a wider
+/// device layout going to stereo (e.g. 5.1 -> stereo) is folded down via
+/// `downmix_to_stereo` below rather than just keeping the front pair. Any
+/// other mismatch (e.g. stereo -> 5.1) cycles the source channels in order.
+fn remix_channels(samples: &[f32], from_channels: usize, to_channels: usize) -> Vec<f32> {
+    if from_channels == to_channels || from_channels == 0 || to_channels == 0 {
+        return samples.to_vec();
+    }
+
+    if to_channels == 1 {
+        samples
+            .chunks(from_channels)
+            .map(|frame| frame.iter().sum::<f32>() / from_channels as f32)
+            .collect()
+    } else if from_channels == 1 {
+        samples
+            .iter()
+            .flat_map(|&sample| std::iter::repeat(sample).take(to_channels))
+            .collect()
+    } else if to_channels == 2 {
+        downmix_to_stereo(samples, from_channels)
+    } else {
+        samples
+            .chunks(from_channels)
+            .flat_map(|frame| (0..to_channels).map(move |ch| frame[ch % from_channels]))
+            .collect()
+    }
+}
+
+/// ITU-R BS.775-style weighted downmix from a wider device layout down to
+/// stereo, assuming the standard FL,FR,FC,LFE,BL,BR,... channel order: front
+/// left/right pass through at full gain, the center channel (dialogue) is
+/// folded into *both* outputs at -3dB (0.707) instead of being dropped, LFE
+/// is non-directional and left out rather than guessing a pan for it, and any
+/// remaining surround/side channels alternate into left/right at -3dB. This
+/// replaces a naive `frame[0]`/`frame[1]` channel selection, which silently
+/// discarded center/LFE/surround content entirely.
+fn downmix_to_stereo(samples: &[f32], from_channels: usize) -> Vec<f32> {
+    const CENTER_GAIN: f32 = 0.707;
+    const SURROUND_GAIN: f32 = 0.707;
+
+    samples
+        .chunks(from_channels)
+        .flat_map(|frame| {
+            let mut l = frame[0];
+            let mut r = frame[1];
+            let mut next_is_left = true;
+            for (ch, &sample) in frame.iter().enumerate().skip(2) {
+                if ch == 2 {
+                    l += sample * CENTER_GAIN;
+                    r += sample * CENTER_GAIN;
+                } else if ch == 3 {
+                    // LFE - non-directional, dropped rather than panned.
+                } else {
+                    if next_is_left {
+                        l += sample * SURROUND_GAIN;
+                    } else {
+                        r += sample * SURROUND_GAIN;
+                    }
+                    next_is_left = !next_is_left;
+                }
+            }
+            [l.clamp(-1.0, 1.0), r.clamp(-1.0, 1.0)]
+        })
+        .collect()
+}
+
+/// Mix two interleaved `f32` streams (already at a common sample rate/channel count)
+/// sample-by-sample: `out[i] = clamp(a[i]*gain_a + b[i]*gain_b, -1.0, 1.0)`. Missing
+/// samples on the shorter buffer are treated as silence for that source only.
+fn mix_samples(a: &[f32], gain_a: f32, b: &[f32], gain_b: f32) -> Vec<f32> {
+    let len = a.len().max(b.len());
+    let mut out = Vec::with_capacity(len);
+    for i in 0..len {
+        let sa = a.get(i).copied().unwrap_or(0.0) * gain_a;
+        let sb = b.get(i).copied().unwrap_or(0.0) * gain_b;
+        out.push((sa + sb).clamp(-1.0, 1.0));
+    }
+    out
+}
+
+/// Capture system (loopback) audio and microphone audio concurrently, mixing them
+/// sample-by-sample into a single WAV file. Each source feeds its own `mpsc` channel
+/// so an underrun on one (e.g. the mic going quiet) only substitutes silence for that
+/// source, not the whole mixed frame. `mic_gain`/`system_gain` let the caller balance
+/// the two sources independently before the mix.
+pub fn start_dual_audio_capture(
+    output_path: String,
+    mic_gain: f32,
+    system_gain: f32,
+) -> Result<(), String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use hound::{WavSpec, WavWriter};
+
+    let mut state = AUDIO_STATE.lock().unwrap();
+    if state.is_recording {
+        return Err("Audio capture already in progress".to_string());
+    }
+
+    let (stop_tx, stop_rx) = channel::<()>();
+
+    let recording_thread = thread::spawn(move || {
+        let host = cpal::default_host();
+
+        let system_device = match resolve_loopback_device(&host) {
+            Some(d) => d,
+            None => {
+                eprintln!("No system-audio loopback device available");
+                return;
+            }
+        };
+        let mic_device = match host.default_input_device() {
+            Some(d) => d,
+            None => {
+                eprintln!("No microphone input device available");
+                return;
+            }
+        };
+
+        println!("System audio device: {}", system_device.name().unwrap_or_default());
+        println!("Microphone device: {}", mic_device.name().unwrap_or_default());
+
+        #[cfg(target_os = "windows")]
+        let system_config = system_device.default_output_config();
+        #[cfg(not(target_os = "windows"))]
+        let system_config = system_device.default_input_config();
+
+        let system_config = match system_config {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Failed to get system audio config: {}", e);
+                return;
+            }
+        };
+        let mic_config = match mic_device.default_input_config() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Failed to get microphone config: {}", e);
+                return;
+            }
+        };
+
+        let system_rate = system_config.sample_rate().0;
+        let system_channels = system_config.channels() as usize;
+        let mic_rate = mic_config.sample_rate().0;
+        let mic_channels = mic_config.channels() as usize;
+
+        // Mix at the system audio's rate/channel count; the mic stream is resampled
+        // and remixed (see `remix_channels`) to match before mixing.
+        let out_rate = system_rate;
+        let out_channels = system_channels.max(1);
+
+        let (system_tx, system_rx) = channel::<Vec<f32>>();
+        let (mic_tx, mic_rx) = channel::<Vec<f32>>();
+
+        let system_stream = system_device.build_input_stream(
+            &system_config.clone().into(),
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let _ = system_tx.send(data.to_vec());
+            },
+            move |err| eprintln!("System audio stream error: {}", err),
+            None,
+        );
+        let mic_stream = mic_device.build_input_stream(
+            &mic_config.clone().into(),
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let _ = mic_tx.send(data.to_vec());
+            },
+            move |err| eprintln!("Microphone stream error: {}", err),
+            None,
+        );
+
+        let (system_stream, mic_stream) = match (system_stream, mic_stream) {
+            (Ok(s), Ok(m)) => (s, m),
+            _ => {
+                eprintln!("Failed to build one or both input streams for mixing");
+                return;
+            }
+        };
+
+        if system_stream.play().is_err() || mic_stream.play().is_err() {
+            eprintln!("Failed to start one or both audio streams");
+            return;
+        }
+
+        let spec = WavSpec {
+            channels: out_channels as u16,
+            sample_rate: out_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = match WavWriter::create(&output_path, spec) {
+            Ok(w) => w,
+            Err(e) => {
+                eprintln!("Failed to create WAV writer: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            match stop_rx.try_recv() {
+                Ok(_) | Err(std::sync::mpsc::TryRecvError::Disconnected) => break,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            }
+
+            // Substitute silence for whichever source has no data ready this tick,
+            // rather than blocking the other source or dropping the whole frame.
+            let system_samples = system_rx.recv_timeout(std::time::Duration::from_millis(50)).unwrap_or_default();
+            let mic_samples_raw = mic_rx.try_recv().unwrap_or_default();
+            let mic_resampled = resample_linear(&mic_samples_raw, mic_channels.max(1), mic_rate, out_rate);
+            let mic_samples = remix_channels(&mic_resampled, mic_channels.max(1), out_channels);
+
+            let mixed = mix_samples(&system_samples, system_gain, &mic_samples, mic_gain);
+            for sample in mixed {
+                let sample_i16 = (sample * i16::MAX as f32) as i16;
+                if let Err(e) = writer.write_sample(sample_i16) {
+                    eprintln!("Failed to write mixed sample: {}", e);
+                    break;
+                }
+            }
+        }
+
+        if let Err(e) = writer.finalize() {
+            eprintln!("Failed to finalize mixed WAV file: {}", e);
+        }
+        drop(system_stream);
+        drop(mic_stream);
+    });
+
+    state.is_recording = true;
+    state.stop_signal = Some(stop_tx);
+    state.recording_thread = Some(recording_thread);
+
+    Ok(())
+}
+
+/// Start a background audio-chunking capture: captured samples are sliced into
+/// fixed-duration chunks and, if a `Transcriber` is provided, each chunk is fed to it
+/// as soon as it's full. Results are collected and written out by `stop_caption_chunking`
+/// as an `.srt` sidecar, with timestamps relative to when this capture started (the same
+/// `audio_start_time` the caller aligns against the muxed video).
+#[cfg(windows)]
+pub fn start_caption_chunking(
+    chunk_seconds: f64,
+    transcriber: Option<Arc<dyn Transcriber>>,
+) -> Result<(), String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    let mut state = CAPTION_STATE.lock().unwrap();
+    if state.is_capturing {
+        return Err("Caption chunking already in progress".to_string());
+    }
+
+    let (stop_tx, stop_rx) = channel::<()>();
+    let (chunk_tx, chunk_rx) = channel::<CaptionChunk>();
+
+    let capture_thread = thread::spawn(move || {
+        let host = cpal::default_host();
+        let device = match host.default_output_device() {
+            Some(d) => d,
+            None => {
+                eprintln!("No default output device available for caption chunking");
+                return;
+            }
+        };
+
+        let config = match device.default_output_config() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Failed to get default output config: {}", e);
+                return;
+            }
+        };
+
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let (audio_tx, audio_rx) = channel::<Vec<f32>>();
+
+        let stream_config: cpal::StreamConfig = config.clone().into();
+        let stream_result = match config.sample_format() {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &stream_config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    let _ = audio_tx.send(data.to_vec());
+                },
+                move |err| eprintln!("Caption audio stream error: {}", err),
+                None,
+            ),
+            _ => {
+                eprintln!("Caption chunking only supports F32 input streams currently");
+                return;
+            }
+        };
+
+        let stream = match stream_result {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Failed to build caption input stream: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = stream.play() {
+            eprintln!("Failed to start caption audio stream: {}", e);
+            return;
+        }
+
+        let samples_per_chunk = (sample_rate as f64 * chunk_seconds) as usize * channels;
+        let mut buffer: Vec<f32> = Vec::with_capacity(samples_per_chunk);
+        let mut chunk_index: u64 = 0;
+
+        loop {
+            match stop_rx.try_recv() {
+                Ok(_) | Err(std::sync::mpsc::TryRecvError::Disconnected) => break,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            }
+
+            match audio_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+                Ok(samples) => buffer.extend(samples),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            while buffer.len() >= samples_per_chunk && samples_per_chunk > 0 {
+                let pcm: Vec<f32> = buffer.drain(0..samples_per_chunk).collect();
+                let start_secs = chunk_index as f64 * chunk_seconds;
+                let end_secs = start_secs + chunk_seconds;
+                chunk_index += 1;
+                let _ = chunk_tx.send(CaptionChunk { start_secs, end_secs, pcm, sample_rate });
+            }
+        }
+
+        // Flush whatever partial chunk remains so the last few seconds aren't dropped.
+        if !buffer.is_empty() {
+            let start_secs = chunk_index as f64 * chunk_seconds;
+            let end_secs = start_secs + (buffer.len() as f64 / (sample_rate as f64 * channels as f64));
+            let _ = chunk_tx.send(CaptionChunk { start_secs, end_secs, pcm: buffer, sample_rate });
+        }
+
+        drop(stream);
+    });
+
+    let transcribe_thread = thread::spawn(move || {
+        let mut entries = Vec::new();
+        for chunk in chunk_rx {
+            let Some(ref transcriber) = transcriber else { continue };
+            match transcriber.transcribe(&chunk.pcm, chunk.sample_rate) {
+                Ok(text) if !text.trim().is_empty() => {
+                    entries.push((chunk.start_secs, chunk.end_secs, text));
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Transcriber error on chunk [{:.1},{:.1}]: {}", chunk.start_secs, chunk.end_secs, e),
+            }
+        }
+        entries
+    });
+
+    state.is_capturing = true;
+    state.stop_signal = Some(stop_tx);
+    state.capture_thread = Some(capture_thread);
+    state.transcribe_thread = Some(transcribe_thread);
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn start_caption_chunking(
+    _chunk_seconds: f64,
+    _transcriber: Option<Arc<dyn Transcriber>>,
+) -> Result<(), String> {
+    Err("Caption chunking is only supported on Windows".to_string())
+}
+
+/// Stop caption chunking and write the collected results as an `.srt` sidecar next to
+/// `video_output_path` (i.e. `foo.mp4` -> `foo.srt`).
+pub fn stop_caption_chunking(video_output_path: &str) -> Result<Option<String>, String> {
+    let mut state = CAPTION_STATE.lock().unwrap();
+    if !state.is_capturing {
+        return Ok(None);
+    }
+
+    if let Some(stop_signal) = state.stop_signal.take() {
+        let _ = stop_signal.send(());
+    }
+
+    let capture_thread = state.capture_thread.take();
+    let transcribe_thread = state.transcribe_thread.take();
+    state.is_capturing = false;
+    drop(state);
+
+    if let Some(t) = capture_thread {
+        let _ = t.join();
+    }
+
+    let entries = transcribe_thread
+        .and_then(|t| t.join().ok())
+        .unwrap_or_default();
+
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let srt_path = std::path::Path::new(video_output_path).with_extension("srt");
+    let srt_content = entries
+        .iter()
+        .enumerate()
+        .map(|(i, (start, end, text))| format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            seconds_to_srt_timestamp(*start),
+            seconds_to_srt_timestamp(*end),
+            text
+        ))
+        .collect::<String>();
+
+    std::fs::write(&srt_path, srt_content)
+        .map_err(|e| format!("Failed to write caption sidecar: {}", e))?;
+
+    Ok(Some(srt_path.to_string_lossy().to_string()))
+}
+
+struct PipedAudioState {
+    is_capturing: bool,
+    stop_signal: Option<Sender<()>>,
+    capture_thread: Option<thread::JoinHandle<()>>,
+}
+
+lazy_static::lazy_static! {
+    static ref PIPED_AUDIO_STATE: Arc<Mutex<PipedAudioState>> = Arc::new(Mutex::new(PipedAudioState {
+        is_capturing: false,
+        stop_signal: None,
+        capture_thread: None,
+    }));
+}
+
+/// Capture WASAPI loopback audio and write it directly into `stdin` as raw
+/// interleaved `s16le` samples, for the live-streaming export path where FFmpeg
+/// takes audio via a second piped input (`-f s16le -i pipe:`) instead of a WAV file.
+#[cfg(windows)]
+pub fn start_audio_capture_piped(
+    mut stdin: std::process::ChildStdin,
+    target_sample_rate: u32,
+    target_channels: u16,
+) -> Result<(), String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use std::io::Write;
+
+    let mut state = PIPED_AUDIO_STATE.lock().unwrap();
+    if state.is_capturing {
+        return Err("Piped audio capture already in progress".to_string());
+    }
+
+    let (stop_tx, stop_rx) = channel::<()>();
+
+    let capture_thread = thread::spawn(move || {
+        let host = cpal::default_host();
+        let device = match host.default_output_device() {
+            Some(d) => d,
+            None => {
+                eprintln!("No default output device available for streaming");
+                return;
+            }
+        };
+
+        let config = match device.default_output_config() {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Failed to get default output config: {}", e);
+                return;
+            }
+        };
+
+        // The stream's native rate/channels may not match what we told FFmpeg to
+        // expect; a mismatch is only logged, and samples are still written at the
+        // device's own rate and channel count.
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as u16;
+        if sample_rate != target_sample_rate || channels != target_channels {
+            println!(
+                "Streaming audio device reports {}Hz/{}ch, FFmpeg was told {}Hz/{}ch",
+                sample_rate, channels, target_sample_rate, target_channels
+            );
+        }
+
+        let (audio_tx, audio_rx) = channel::<Vec<f32>>();
+        let stream_config: cpal::StreamConfig = config.clone().into();
+        let stream_result = match config.sample_format() {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &stream_config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    let _ = audio_tx.send(data.to_vec());
+                },
+                move |err| eprintln!("Streaming audio stream error: {}", err),
+                None,
+            ),
+            _ => {
+                eprintln!("Streaming audio capture only supports F32 input streams currently");
+                return;
+            }
+        };
+
+        let stream = match stream_result {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Failed to build streaming input stream: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = stream.play() {
+            eprintln!("Failed to start streaming audio stream: {}", e);
+            return;
+        }
+
+        loop {
+            match stop_rx.try_recv() {
+                Ok(_) | Err(std::sync::mpsc::TryRecvError::Disconnected) => break,
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            }
+
+            match audio_rx.recv_timeout(std::time::Duration::from_millis(100)) {
+                Ok(samples) => {
+                    let bytes: Vec<u8> = samples
+                        .iter()
+                        .flat_map(|&s| ((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes())
+                        .collect();
+                    if stdin.write_all(&bytes).is_err() {
+                        // FFmpeg process exited (e.g. stream ended) - stop feeding it.
+                        break;
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        drop(stream);
+    });
+
+    state.is_capturing = true;
+    state.stop_signal = Some(stop_tx);
+    state.capture_thread = Some(capture_thread);
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn start_audio_capture_piped(
+    _stdin: std::process::ChildStdin,
+    _target_sample_rate: u32,
+    _target_channels: u16,
+) -> Result<(), String> {
+    Err("WASAPI audio capture is only supported on Windows".to_string())
+}
+
+pub fn stop_audio_capture_piped() -> Result<(), String> {
+    let mut state = PIPED_AUDIO_STATE.lock().unwrap();
+    if !state.is_capturing {
+        return Ok(());
+    }
+
+    if let Some(stop_signal) = state.stop_signal.take() {
+        let _ = stop_signal.send(());
+    }
+    let thread = state.capture_thread.take();
+    state.is_capturing = false;
+    drop(state);
+
+    if let Some(t) = thread {
+        let _ = t.join();
+    }
+
+    Ok(())
+}
+
+fn seconds_to_srt_timestamp(total_seconds: f64) -> String {
+    let total_ms = (total_seconds * 1000.0).round() as i64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let seconds = (total_ms % 60_000) / 1000;
+    let millis = total_ms % 1000;
+    format!("{:02}:{:02}:{:02},{:03}", hours, minutes, seconds, millis)
+}
@@ -0,0 +1,383 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::Emitter;
+
+use crate::export_progress::ExportProgress;
+
+/// Parallel, scene-chunked re-encode of a single already-composited source file,
+/// inspired by Av1an: split the source into N contiguous segments at scene-cut
+/// boundaries, encode each segment concurrently with a forced closed-GOP so the
+/// boundary lands on a keyframe, then losslessly `-c copy` concat the chunks back
+/// together. This keeps most cores busy on long exports instead of a single
+/// libx264 pass idling every core but one.
+///
+/// Falls back with an `Err` (callers should fall back to the single-pass path) when
+/// there's nothing worth chunking: fewer than 2 workers requested, or the source is
+/// too short/uncuttable to produce at least 2 segments.
+#[allow(clippy::too_many_arguments)]
+pub fn export_chunked(
+    input_path: &str,
+    output_path: &str,
+    width: u32,
+    height: u32,
+    crf: &str,
+    preset: &str,
+    workers: Option<usize>,
+    app_handle: &tauri::AppHandle,
+    event_name: &str,
+    ffmpeg_path: &str,
+    ffprobe_path: &str,
+) -> Result<String, String> {
+    let worker_count = workers.unwrap_or_else(|| {
+        thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    });
+
+    if worker_count < 2 {
+        return Err("Chunked export needs at least 2 workers".to_string());
+    }
+
+    let duration = get_duration(ffprobe_path, input_path)?;
+    if duration <= 0.0 {
+        return Err("Source has zero duration".to_string());
+    }
+
+    let boundaries = detect_chunk_boundaries(ffmpeg_path, input_path, duration, worker_count)?;
+    if boundaries.len() < 3 {
+        return Err("Source too short to split into parallel chunks".to_string());
+    }
+
+    let temp_dir = std::env::temp_dir().join(format!(
+        "vibeclips_chunked_export_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| format!("Failed to create chunk temp dir: {}", e))?;
+
+    // Indexed filenames preserve chunk ordering independent of which worker
+    // finishes first.
+    let chunk_count = boundaries.len() - 1;
+    let chunk_paths: Vec<PathBuf> = (0..chunk_count)
+        .map(|i| temp_dir.join(format!("chunk_{:04}.mp4", i)))
+        .collect();
+
+    let work_queue: Arc<Mutex<VecDeque<(usize, f64, f64)>>> = Arc::new(Mutex::new(
+        boundaries
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| (i, w[0], w[1]))
+            .collect(),
+    ));
+    let first_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    // One (frames, elapsed_secs) slot per chunk, updated in place by whichever
+    // worker owns that chunk, so `emit_progress` can sum across every chunk
+    // regardless of completion order - the same per-chunk-frame-count rollup
+    // an Av1an-style progress bar shows.
+    let chunk_progress: Arc<Mutex<Vec<(u64, f64)>>> =
+        Arc::new(Mutex::new(vec![(0, 0.0); chunk_count]));
+    let app_handle = app_handle.clone();
+    let event_name = event_name.to_string();
+
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            let work_queue = Arc::clone(&work_queue);
+            let first_error = Arc::clone(&first_error);
+            let chunk_paths = &chunk_paths;
+            let chunk_progress = Arc::clone(&chunk_progress);
+            let app_handle = app_handle.clone();
+            let event_name = &event_name;
+
+            scope.spawn(move || loop {
+                if first_error.lock().unwrap().is_some() {
+                    break;
+                }
+
+                let next = work_queue.lock().unwrap().pop_front();
+                let (idx, start, end) = match next {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                if let Err(e) = encode_chunk(
+                    ffmpeg_path,
+                    input_path,
+                    &chunk_paths[idx],
+                    start,
+                    end,
+                    width,
+                    height,
+                    crf,
+                    preset,
+                    idx,
+                    &chunk_progress,
+                    &app_handle,
+                    event_name,
+                    duration,
+                ) {
+                    let mut guard = first_error.lock().unwrap();
+                    if guard.is_none() {
+                        *guard = Some(format!("Chunk {} failed: {}", idx, e));
+                    }
+                    break;
+                }
+            });
+        }
+    });
+
+    // A single failed worker aborts the whole export; propagate its stderr.
+    if let Some(e) = first_error.lock().unwrap().take() {
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        return Err(e);
+    }
+
+    let result = concat_chunks(ffmpeg_path, &chunk_paths, output_path);
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    if result.is_ok() {
+        emit_progress(&app_handle, &event_name, &chunk_progress, duration, true);
+    }
+    result
+}
+
+/// Sum every chunk's own `(frames, elapsed_secs)` slot into one global tick and
+/// emit it, the same `ExportProgress` shape `export_progress::run_with_progress`
+/// emits for the single-pass path, so the frontend's progress bar doesn't need
+/// to know which export strategy is running underneath it.
+fn emit_progress(
+    app_handle: &tauri::AppHandle,
+    event_name: &str,
+    chunk_progress: &Arc<Mutex<Vec<(u64, f64)>>>,
+    total_duration_secs: f64,
+    done: bool,
+) {
+    let (frame, elapsed) = chunk_progress
+        .lock()
+        .unwrap()
+        .iter()
+        .fold((0u64, 0.0), |(f, e), (cf, ce)| (f + cf, e + ce));
+    let percent = if done {
+        100.0
+    } else if total_duration_secs > 0.0 {
+        (elapsed / total_duration_secs * 100.0).clamp(0.0, 100.0)
+    } else {
+        0.0
+    };
+    let _ = app_handle.emit(event_name, ExportProgress {
+        percent,
+        frame,
+        fps: 0.0,
+        speed: 0.0,
+        done,
+    });
+}
+
+/// Compute the boundary timestamps of up to `target_chunks` chunks (so up to
+/// `target_chunks + 1` timestamps), always including `0.0` and `duration`. Prefers scene-cut boundaries (so chunks don't split mid-motion in a
+/// visually jarring way); if scene detection finds nothing, falls back to evenly
+/// spaced boundaries so the export can still be parallelized.
+fn detect_chunk_boundaries(ffmpeg_path: &str, input_path: &str, duration: f64, target_chunks: usize) -> Result<Vec<f64>, String> {
+    let cuts = detect_scene_cuts(ffmpeg_path, input_path, 0.3)?;
+
+    let mut boundaries = vec![0.0];
+    if cuts.is_empty() {
+        let step = duration / target_chunks as f64;
+        for i in 1..target_chunks {
+            boundaries.push(i as f64 * step);
+        }
+    } else if cuts.len() <= target_chunks - 1 {
+        boundaries.extend(cuts);
+    } else {
+        // More cuts than we have workers for: spread our picks evenly across the
+        // detected list rather than chunking on every single scene change.
+        let stride = cuts.len() as f64 / (target_chunks - 1) as f64;
+        for i in 0..(target_chunks - 1) {
+            if let Some(&c) = cuts.get((i as f64 * stride) as usize) {
+                boundaries.push(c);
+            }
+        }
+    }
+    boundaries.push(duration);
+    boundaries.dedup_by(|a, b| (*a - *b).abs() < 0.01);
+
+    Ok(boundaries)
+}
+
+fn get_duration(ffprobe_path: &str, input_path: &str) -> Result<f64, String> {
+    let output = crate::hidden_command(ffprobe_path)
+        .arg("-v").arg("error")
+        .arg("-show_entries").arg("format=duration")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input_path)
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err("Failed to get source duration".to_string());
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| format!("Failed to parse duration: {}", e))
+}
+
+/// Run FFmpeg's scene filter over `input_path` and parse `showinfo`'s `pts_time:`
+/// fields for every frame selected as a scene change.
+fn detect_scene_cuts(ffmpeg_path: &str, input_path: &str, threshold: f64) -> Result<Vec<f64>, String> {
+    let output = crate::hidden_command(ffmpeg_path)
+        .arg("-i").arg(input_path)
+        .arg("-filter:v").arg(format!("select='gt(scene,{:.3})',showinfo", threshold))
+        .arg("-f").arg("null")
+        .arg("-")
+        .arg("-hide_banner")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg scene detection: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut cuts = Vec::new();
+    for line in stderr.lines() {
+        if let Some(idx) = line.find("pts_time:") {
+            let rest = &line[idx + "pts_time:".len()..];
+            if let Some(ts) = rest.split_whitespace().next() {
+                if let Ok(secs) = ts.parse::<f64>() {
+                    cuts.push(secs);
+                }
+            }
+        }
+    }
+    cuts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(cuts)
+}
+
+/// Encode one `[start, end)` window of `input_path` to `chunk_path`, forcing a
+/// closed-GOP with a keyframe at frame 0 so chunk boundaries align with keyframes
+/// for a glitch-free `-c copy` concat afterwards. Parses this chunk's own
+/// `-progress pipe:1` output into `chunk_progress[chunk_idx]` as it runs, and
+/// re-emits the global rollup across all chunks after every update.
+#[allow(clippy::too_many_arguments)]
+fn encode_chunk(
+    ffmpeg_path: &str,
+    input_path: &str,
+    chunk_path: &Path,
+    start: f64,
+    end: f64,
+    width: u32,
+    height: u32,
+    crf: &str,
+    preset: &str,
+    chunk_idx: usize,
+    chunk_progress: &Arc<Mutex<Vec<(u64, f64)>>>,
+    app_handle: &tauri::AppHandle,
+    event_name: &str,
+    total_duration_secs: f64,
+) -> Result<(), String> {
+    let chunk_duration = (end - start).max(0.0);
+
+    let mut cmd = crate::hidden_command(ffmpeg_path);
+    cmd.arg("-y")
+        .arg("-ss").arg(format!("{:.3}", start))
+        .arg("-i").arg(input_path)
+        .arg("-t").arg(format!("{:.3}", chunk_duration));
+
+    if width > 0 && height > 0 {
+        cmd.arg("-vf").arg(format!("scale={}:{}", width, height));
+    }
+
+    cmd.arg("-c:v").arg("libx264")
+        .arg("-preset").arg(preset)
+        .arg("-crf").arg(crf)
+        .arg("-g").arg("48")
+        .arg("-keyint_min").arg("48")
+        .arg("-sc_threshold").arg("0")
+        .arg("-force_key_frames").arg("expr:eq(n,0)")
+        .arg("-c:a").arg("aac")
+        .arg("-b:a").arg("192k")
+        .arg("-pix_fmt").arg("yuv420p")
+        .arg(chunk_path)
+        .arg("-progress").arg("pipe:1")
+        .arg("-nostats")
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error");
+
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    let stdout = child.stdout.take().ok_or_else(|| "Failed to capture FFmpeg stdout".to_string())?;
+    let mut stderr_pipe = child.stderr.take().ok_or_else(|| "Failed to capture FFmpeg stderr".to_string())?;
+
+    use std::io::{BufRead, BufReader, Read};
+    let mut stderr_buf = Vec::new();
+    thread::scope(|s| {
+        s.spawn(|| {
+            let reader = BufReader::new(stdout);
+            let mut frame = 0u64;
+            for line in reader.lines().map_while(Result::ok) {
+                let Some((key, value)) = line.split_once('=') else { continue };
+                let value = value.trim();
+                match key {
+                    "frame" => frame = value.parse().unwrap_or(frame),
+                    "out_time_ms" => {
+                        if let Ok(out_time_ms) = value.parse::<i64>() {
+                            let elapsed = (out_time_ms as f64 / 1_000_000.0).clamp(0.0, chunk_duration);
+                            chunk_progress.lock().unwrap()[chunk_idx] = (frame, elapsed);
+                            emit_progress(app_handle, event_name, chunk_progress, total_duration_secs, false);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+        let _ = stderr_pipe.read_to_end(&mut stderr_buf);
+    });
+
+    let status = child.wait().map_err(|e| format!("Failed to wait for FFmpeg: {}", e))?;
+
+    if status.success() {
+        let mut guard = chunk_progress.lock().unwrap();
+        guard[chunk_idx].1 = chunk_duration;
+        drop(guard);
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&stderr_buf).to_string())
+    }
+}
+
+/// Losslessly join the encoded chunks, in index order, via the concat demuxer.
+fn concat_chunks(ffmpeg_path: &str, chunk_paths: &[PathBuf], output_path: &str) -> Result<String, String> {
+    let concat_list = chunk_paths[0]
+        .parent()
+        .expect("chunk path has a parent temp dir")
+        .join("concat.txt");
+
+    let concat_content: String = chunk_paths
+        .iter()
+        .map(|p| format!("file '{}'\n", p.to_string_lossy().replace('\\', "/")))
+        .collect();
+    std::fs::write(&concat_list, concat_content)
+        .map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+    let output = crate::hidden_command(ffmpeg_path)
+        .arg("-y")
+        .arg("-f").arg("concat")
+        .arg("-safe").arg("0")
+        .arg("-i").arg(&concat_list)
+        .arg("-c").arg("copy")
+        .arg(output_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg concat: {}", e))?;
+
+    if output.status.success() {
+        Ok(output_path.to_string())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Chunk concat failed: {}", error))
+    }
+}
@@ -0,0 +1,197 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// One per-clip trim/scale/eq job for `export_video_blocking`'s multi-clip
+/// track-0 path: trim `input_path` to `[trim_start, trim_start+duration)`,
+/// apply `video_args` (the intermediate-vs-final codec args the caller already
+/// decided on) and `vf_filter`, and write the result to `output_path`.
+pub struct ClipTrimJob {
+    pub index: usize,
+    pub input_path: String,
+    pub trim_start: f64,
+    pub duration: f64,
+    /// Args a hardware backend needs placed *before* `-i` (currently only
+    /// VAAPI's render-node device init) - see `encoder_backend::EncoderPlan`.
+    /// Empty for the software path and the fixed-intermediate path.
+    pub input_args: Vec<String>,
+    pub video_args: Vec<String>,
+    pub vf_filter: Option<String>,
+    /// Fast-forward regions on this clip's own `[0, duration)` timeline - see
+    /// `speed_ramp`. Empty means no retiming, same as today.
+    pub speed_segments: Vec<crate::speed_ramp::SpeedSegment>,
+    pub output_path: PathBuf,
+    /// Pixel format and color-tagging args this clip's own source needs (10-bit
+    /// + PQ/HLG tags for HDR, plain 8-bit otherwise) - see `crate::hdr`.
+    pub pix_fmt: &'static str,
+    pub color_args: Vec<String>,
+    /// Exact project output frame rate (see `crate::frame_rate`), forced via
+    /// `-r`/`-video_track_timescale` so every trimmed clip lands on the same
+    /// cadence ahead of the concat demuxer.
+    pub fps: crate::frame_rate::FrameRate,
+    /// Whether this clip's own source frame rate differs from `fps` and needs
+    /// an explicit `fps=` filter rather than relying on `-r` alone to retime.
+    pub needs_fps_filter: bool,
+    /// Resolved path to the bundled (or PATH-fallback) ffmpeg binary - see
+    /// `find_ffmpeg_binary` in `lib.rs`. Threaded in from the caller rather than
+    /// re-resolved per job since every job in a pool shares the same binary.
+    pub ffmpeg_path: String,
+}
+
+/// A `completed/total` tick emitted as each job in the pool finishes, so the
+/// UI can show trim progress on long multi-clip timelines.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrimPoolProgress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Run `jobs` across a bounded worker pool (default `available_parallelism`,
+/// clamped to the job count), modeled on `chunked_export`'s work-queue pool:
+/// workers pull from a shared queue so completion order never matters - each
+/// job's `output_path` is keyed by `index`, so the caller's `concat_list.txt`
+/// stays deterministically ordered regardless of which worker finishes first.
+/// `on_progress` fires after each successful job with `(completed, total)`.
+///
+/// If any job fails, no further queued jobs are started and the first error
+/// is returned once every worker has drained; in-flight sibling jobs are left
+/// to finish on their own rather than force-killed, since these are short
+/// single-clip trims, not long-running processes worth racing to kill.
+pub fn run_trim_pool(
+    jobs: Vec<ClipTrimJob>,
+    workers: Option<usize>,
+    on_progress: impl Fn(usize, usize) + Send + Sync,
+) -> Result<(), String> {
+    let total = jobs.len();
+    if total == 0 {
+        return Ok(());
+    }
+
+    let worker_count = workers
+        .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .clamp(1, total);
+
+    let completed = Arc::new(AtomicUsize::new(0));
+    let work_queue: Arc<Mutex<VecDeque<ClipTrimJob>>> = Arc::new(Mutex::new(jobs.into_iter().collect()));
+    let first_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            let work_queue = Arc::clone(&work_queue);
+            let first_error = Arc::clone(&first_error);
+            let completed = Arc::clone(&completed);
+            let on_progress = &on_progress;
+
+            scope.spawn(move || loop {
+                if first_error.lock().unwrap().is_some() {
+                    break;
+                }
+
+                let job = match work_queue.lock().unwrap().pop_front() {
+                    Some(job) => job,
+                    None => break,
+                };
+                let index = job.index;
+
+                if let Err(e) = run_trim_job(&job) {
+                    let mut guard = first_error.lock().unwrap();
+                    if guard.is_none() {
+                        *guard = Some(format!("Clip {} failed: {}", index, e));
+                    }
+                    break;
+                }
+
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                on_progress(done, total);
+            });
+        }
+    });
+
+    match Arc::try_unwrap(first_error).unwrap().into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+fn run_trim_job(job: &ClipTrimJob) -> Result<(), String> {
+    let mut cmd = crate::hidden_command(&job.ffmpeg_path);
+    cmd.arg("-y");
+    for arg in &job.input_args {
+        cmd.arg(arg);
+    }
+
+    if job.trim_start > 0.0 {
+        cmd.arg("-ss").arg(format!("{:.3}", job.trim_start));
+    }
+
+    cmd.arg("-i").arg(&job.input_path);
+
+    if job.duration > 0.0 {
+        cmd.arg("-t").arg(format!("{:.3}", job.duration));
+    }
+
+    for arg in &job.video_args {
+        cmd.arg(arg);
+    }
+    cmd.arg("-c:a").arg("aac");
+    cmd.arg("-b:a").arg("192k");
+    cmd.arg("-pix_fmt").arg(job.pix_fmt);
+    for arg in &job.color_args {
+        cmd.arg(arg);
+    }
+    cmd.arg("-r").arg(job.fps.as_ffmpeg_arg());
+    cmd.arg("-video_track_timescale").arg(job.fps.num.to_string());
+
+    if job.speed_segments.is_empty() {
+        let fps_filter = job.needs_fps_filter.then(|| format!("fps={}", job.fps.as_ffmpeg_arg()));
+        let vf = match (&fps_filter, &job.vf_filter) {
+            (Some(fps), Some(vf)) => Some(format!("{},{}", fps, vf)),
+            (Some(fps), None) => Some(fps.clone()),
+            (None, Some(vf)) => Some(vf.clone()),
+            (None, None) => None,
+        };
+        if let Some(vf) = vf {
+            cmd.arg("-vf").arg(vf);
+        }
+    } else {
+        // A speed ramp retimes the whole stream via trim/concat, so it has to
+        // go through `-filter_complex` (and explicit `-map`s) rather than the
+        // plain `-vf` chain above - any other per-clip filters are folded in
+        // as one more stage on top of the ramped `[vramp]` output.
+        let mut filter_complex = crate::speed_ramp::build_filter_complex(
+            "0:v", "0:a", &job.speed_segments, job.duration, "vramp", "aout",
+        );
+        let fps_filter = job.needs_fps_filter.then(|| format!("fps={}", job.fps.as_ffmpeg_arg()));
+        let post_ramp_filter = match (&fps_filter, &job.vf_filter) {
+            (Some(fps), Some(vf)) => Some(format!("{},{}", fps, vf)),
+            (Some(fps), None) => Some(fps.clone()),
+            (None, Some(vf)) => Some(vf.clone()),
+            (None, None) => None,
+        };
+        let vout_label = match &post_ramp_filter {
+            Some(vf) => {
+                filter_complex.push_str(&format!(";[vramp]{}[vout]", vf));
+                "vout"
+            }
+            None => "vramp",
+        };
+        cmd.arg("-filter_complex").arg(filter_complex);
+        cmd.arg("-map").arg(format!("[{}]", vout_label));
+        cmd.arg("-map").arg("[aout]");
+    }
+
+    cmd.arg(&job.output_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("quiet")
+        .arg("-nostats");
+
+    let output = cmd.output().map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Hardware/software video encoder backend for the export pipeline (as distinct
+/// from `screen_capture::VideoEncoder`, which drives live recording). `Auto` lets
+/// callers fall back to software libx264 when nothing else is available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EncoderBackend {
+    Auto,
+    Libx264,
+    H264Nvenc,
+    HevcNvenc,
+    H264Vaapi,
+    H264Qsv,
+    H264Videotoolbox,
+}
+
+impl EncoderBackend {
+    /// The literal `-c:v` name FFmpeg expects for this backend.
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            EncoderBackend::Auto | EncoderBackend::Libx264 => "libx264",
+            EncoderBackend::H264Nvenc => "h264_nvenc",
+            EncoderBackend::HevcNvenc => "hevc_nvenc",
+            EncoderBackend::H264Vaapi => "h264_vaapi",
+            EncoderBackend::H264Qsv => "h264_qsv",
+            EncoderBackend::H264Videotoolbox => "h264_videotoolbox",
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref AVAILABLE_BACKENDS: Arc<Mutex<Option<HashSet<String>>>> = Arc::new(Mutex::new(None));
+    static ref AVAILABLE_HWACCELS: Arc<Mutex<Option<HashSet<String>>>> = Arc::new(Mutex::new(None));
+}
+
+/// Run `ffmpeg -encoders` once and cache the set of encoder names it reports.
+/// `ffmpeg_path` should come from `find_ffmpeg_binary` so this probes the same
+/// bundled binary the rest of the export path runs, not whatever (if anything)
+/// happens to be on `PATH`.
+fn detect_available_encoder_names(ffmpeg_path: &str) -> HashSet<String> {
+    let mut cache = AVAILABLE_BACKENDS.lock().unwrap();
+    if let Some(ref names) = *cache {
+        return names.clone();
+    }
+
+    let mut names = HashSet::new();
+    if let Ok(output) = crate::hidden_command(ffmpeg_path).arg("-hide_banner").arg("-encoders").output() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let trimmed = line.trim_start();
+            if !trimmed.starts_with('V') {
+                continue;
+            }
+            if let Some(name) = trimmed.split_whitespace().nth(1) {
+                names.insert(name.to_string());
+            }
+        }
+    }
+
+    *cache = Some(names.clone());
+    names
+}
+
+/// Run `ffmpeg -hwaccels` once and cache the set of hwaccel method names it
+/// reports (e.g. `vaapi`, `cuda`, `videotoolbox`). Separate from the encoder
+/// probe above since an encoder can be compiled in without the runtime
+/// hwaccel actually being usable on this machine (missing driver, no device
+/// node, etc.) - `vaapi_overlay_plan` below needs both to agree before it GPU-
+/// composites instead of falling back to a CPU `overlay`.
+fn detect_available_hwaccels(ffmpeg_path: &str) -> HashSet<String> {
+    let mut cache = AVAILABLE_HWACCELS.lock().unwrap();
+    if let Some(ref names) = *cache {
+        return names.clone();
+    }
+
+    let mut names = HashSet::new();
+    if let Ok(output) = crate::hidden_command(ffmpeg_path).arg("-hide_banner").arg("-hwaccels").output() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines().skip(1) {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                names.insert(trimmed.to_string());
+            }
+        }
+    }
+
+    *cache = Some(names.clone());
+    names
+}
+
+/// Probe `ffmpeg -encoders` for every hardware backend this subsystem knows about,
+/// for a `list_encoders` command the UI can use to populate an encoder picker.
+pub fn list_available_backends(ffmpeg_path: &str) -> Vec<EncoderBackend> {
+    let available = detect_available_encoder_names(ffmpeg_path);
+    let mut backends = vec![EncoderBackend::Libx264];
+    for backend in [
+        EncoderBackend::H264Nvenc,
+        EncoderBackend::HevcNvenc,
+        EncoderBackend::H264Vaapi,
+        EncoderBackend::H264Qsv,
+        EncoderBackend::H264Videotoolbox,
+    ] {
+        if available.contains(backend.ffmpeg_name()) {
+            backends.push(backend);
+        }
+    }
+    backends
+}
+
+/// Resolve a requested backend against what FFmpeg actually reports, falling back
+/// to libx264 if the hardware backend isn't available. `Auto` picks the first
+/// available hardware backend, preferring NVENC, then VAAPI, then QSV, then
+/// VideoToolbox.
+fn resolve_backend(requested: EncoderBackend, ffmpeg_path: &str) -> EncoderBackend {
+    let available = detect_available_encoder_names(ffmpeg_path);
+
+    if requested == EncoderBackend::Auto {
+        for candidate in [
+            EncoderBackend::H264Nvenc,
+            EncoderBackend::H264Vaapi,
+            EncoderBackend::H264Qsv,
+            EncoderBackend::H264Videotoolbox,
+        ] {
+            if available.contains(candidate.ffmpeg_name()) {
+                return candidate;
+            }
+        }
+        return EncoderBackend::Libx264;
+    }
+
+    if requested == EncoderBackend::Libx264 || available.contains(requested.ffmpeg_name()) {
+        requested
+    } else {
+        println!("Requested encoder backend {:?} not available, falling back to libx264", requested);
+        EncoderBackend::Libx264
+    }
+}
+
+/// FFmpeg args to place *before* `-i` for backends that need a device/hwaccel
+/// context set up (currently only VAAPI's render node).
+fn input_device_args(backend: EncoderBackend) -> Vec<String> {
+    match backend {
+        EncoderBackend::H264Vaapi => vec![
+            "-vaapi_device".into(), "/dev/dri/renderD128".into(),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// A video filter fragment this backend needs appended to the filter chain before
+/// the encoder can consume the frames (VAAPI needs frames uploaded into device
+/// memory in NV12; the others encode directly from software frames).
+fn filter_suffix(backend: EncoderBackend) -> Option<&'static str> {
+    match backend {
+        EncoderBackend::H264Vaapi => Some("format=nv12,hwupload"),
+        _ => None,
+    }
+}
+
+/// Translate a CRF-style quality value into this backend's rate-control flags,
+/// appended after `-c:v <name>`.
+fn rate_control_args(backend: EncoderBackend, crf: &str, preset: &str) -> Vec<String> {
+    match backend {
+        EncoderBackend::Auto | EncoderBackend::Libx264 => vec![
+            "-preset".into(), preset.into(),
+            "-crf".into(), crf.into(),
+        ],
+        EncoderBackend::H264Nvenc | EncoderBackend::HevcNvenc => vec![
+            "-preset".into(), "p4".into(),
+            "-rc".into(), "vbr".into(),
+            "-cq".into(), crf.into(),
+        ],
+        EncoderBackend::H264Vaapi => vec![
+            "-qp".into(), crf.into(),
+        ],
+        EncoderBackend::H264Qsv => vec![
+            "-preset".into(), preset.into(),
+            "-global_quality".into(), crf.into(),
+        ],
+        EncoderBackend::H264Videotoolbox => vec![
+            "-q:v".into(), crf.into(),
+        ],
+    }
+}
+
+/// Everything a caller needs to splice a hardware (or software) encoder into an
+/// existing FFmpeg command: args to add before `-i`, an extra filter fragment to
+/// fold into the `-vf`/`filter_complex` chain, and the `-c:v ... <rate control>` tail.
+pub struct EncoderPlan {
+    pub backend: EncoderBackend,
+    pub input_args: Vec<String>,
+    pub filter_suffix: Option<&'static str>,
+    pub codec_args: Vec<String>,
+}
+
+/// Resolve `requested` against what's actually available and build the full plan.
+pub fn plan_for(requested: EncoderBackend, crf: &str, preset: &str, ffmpeg_path: &str) -> EncoderPlan {
+    let backend = resolve_backend(requested, ffmpeg_path);
+    let mut codec_args = vec!["-c:v".to_string(), backend.ffmpeg_name().to_string()];
+    codec_args.extend(rate_control_args(backend, crf, preset));
+
+    EncoderPlan {
+        backend,
+        input_args: input_device_args(backend),
+        filter_suffix: filter_suffix(backend),
+        codec_args,
+    }
+}
+
+/// Run an FFmpeg export job built from `plan`, and if the process exits non-zero
+/// while a hardware backend was in use, rebuild and re-run the exact same job with
+/// plain libx264 so exports never hard-fail just because of a driver/runtime issue.
+pub fn run_with_hardware_fallback<F>(
+    requested: EncoderBackend,
+    crf: &str,
+    preset: &str,
+    ffmpeg_path: &str,
+    mut build_and_run: F,
+) -> Result<std::process::Output, String>
+where
+    F: FnMut(&EncoderPlan) -> Result<std::process::Output, String>,
+{
+    let plan = plan_for(requested, crf, preset, ffmpeg_path);
+    let used_hardware = plan.backend != EncoderBackend::Libx264;
+    let result = build_and_run(&plan)?;
+
+    if result.status.success() || !used_hardware {
+        return Ok(result);
+    }
+
+    println!(
+        "Hardware encode with {:?} failed, retrying with software libx264",
+        plan.backend
+    );
+    let fallback_plan = plan_for(EncoderBackend::Libx264, crf, preset, ffmpeg_path);
+    build_and_run(&fallback_plan)
+}
+
+/// Everything the multi-track overlay pass needs to composite entirely on the
+/// GPU via VAAPI: device-init args for before `-i`, and the `-c:v h264_vaapi`
+/// rate-control args for the output.
+pub struct VaapiOverlayPlan {
+    pub input_args: Vec<String>,
+    pub codec_args: Vec<String>,
+}
+
+/// Only offer the VAAPI GPU-compositing fast-path when `requested` (or, for
+/// `Auto`, whatever it resolves to) is actually VAAPI *and* both the encoder
+/// and the `vaapi` hwaccel are available - otherwise the overlay pass falls
+/// back to the CPU `overlay` filter and plain libx264, same as before this
+/// backend existed.
+pub fn vaapi_overlay_plan(requested: EncoderBackend, crf: &str, preset: &str, ffmpeg_path: &str) -> Option<VaapiOverlayPlan> {
+    if resolve_backend(requested, ffmpeg_path) != EncoderBackend::H264Vaapi {
+        return None;
+    }
+    if !detect_available_hwaccels(ffmpeg_path).contains("vaapi") {
+        return None;
+    }
+
+    let mut codec_args = vec!["-c:v".to_string(), EncoderBackend::H264Vaapi.ffmpeg_name().to_string()];
+    codec_args.extend(rate_control_args(EncoderBackend::H264Vaapi, crf, preset));
+
+    Some(VaapiOverlayPlan {
+        input_args: input_device_args(EncoderBackend::H264Vaapi),
+        codec_args,
+    })
+}
+
+/// Build the `filter_complex` fragment that composites `overlay_input` onto
+/// `base_input` at `(x, y)` with both streams kept on the GPU the whole way:
+/// each input is uploaded into VAAPI surfaces once, then `overlay_vaapi` does
+/// the compositing in device memory - unlike the CPU `overlay` filter, there's
+/// no hwdownload/hwupload round trip between the scale/upload step and the
+/// overlay itself. `base_input`/`overlay_input` are FFmpeg stream specifiers
+/// like `"0:v"`; `out_label` is the filter label the caller maps from.
+pub fn vaapi_overlay_filter(base_input: &str, overlay_input: &str, x: u32, y: u32, out_label: &str) -> String {
+    format!(
+        "[{base}]format=nv12,hwupload[ovbase];[{ovl}]format=nv12,hwupload[ovtop];\
+         [ovbase][ovtop]overlay_vaapi=x={x}:y={y}[{out}]",
+        base = base_input,
+        ovl = overlay_input,
+        x = x,
+        y = y,
+        out = out_label,
+    )
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::Emitter;
+
+lazy_static::lazy_static! {
+    // The currently running tracked export, if any, so `cancel_export` can kill it.
+    // Only one export is tracked at a time - a second concurrent export silently
+    // replaces the tracked handle, same as the rest of this codebase's single-session
+    // capture/recording state.
+    static ref EXPORT_CHILD: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
+}
+
+/// A single progress tick parsed from FFmpeg's `-progress pipe:1` output, emitted
+/// to the frontend as `event_name`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub percent: f64,
+    pub frame: u64,
+    pub fps: f64,
+    pub speed: f64,
+    pub done: bool,
+}
+
+/// Run `cmd` (already configured with its input/filter/output args, but not yet
+/// spawned) as a progress-reporting FFmpeg job: appends `-progress pipe:1 -nostats`,
+/// spawns it, and parses the periodic `key=value` lines FFmpeg writes to stdout on a
+/// reader thread. `out_time_ms` is converted against `total_duration_secs` into a
+/// 0-100 percentage and emitted via `app_handle.emit(event_name, ExportProgress)`.
+/// Tracks the child process so `cancel_export` can kill it mid-run.
+pub fn run_with_progress(
+    mut cmd: Command,
+    app_handle: &tauri::AppHandle,
+    total_duration_secs: f64,
+    event_name: &str,
+) -> Result<std::process::Output, String> {
+    cmd.arg("-progress").arg("pipe:1");
+    cmd.arg("-nostats");
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to spawn FFmpeg: {}", e))?;
+    let stdout = child.stdout.take().ok_or_else(|| "Failed to capture FFmpeg stdout".to_string())?;
+    let mut stderr_pipe = child.stderr.take().ok_or_else(|| "Failed to capture FFmpeg stderr".to_string())?;
+
+    *EXPORT_CHILD.lock().unwrap() = Some(child);
+
+    let app_handle_for_reader = app_handle.clone();
+    let event_name_owned = event_name.to_string();
+    let reader_thread = thread::spawn(move || {
+        let reader = BufReader::new(stdout);
+        let mut frame = 0u64;
+        let mut fps = 0.0;
+        let mut speed = 0.0;
+
+        for line in reader.lines().map_while(Result::ok) {
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let value = value.trim();
+
+            match key {
+                "frame" => frame = value.parse().unwrap_or(frame),
+                "fps" => fps = value.parse().unwrap_or(fps),
+                "speed" => speed = value.trim_end_matches('x').parse().unwrap_or(speed),
+                "out_time_ms" => {
+                    if let Ok(out_time_ms) = value.parse::<i64>() {
+                        let percent = if total_duration_secs > 0.0 {
+                            ((out_time_ms as f64 / 1_000_000.0) / total_duration_secs * 100.0)
+                                .clamp(0.0, 100.0)
+                        } else {
+                            0.0
+                        };
+                        let _ = app_handle_for_reader.emit(&event_name_owned, ExportProgress {
+                            percent, frame, fps, speed, done: false,
+                        });
+                    }
+                }
+                "progress" if value == "end" => {
+                    let _ = app_handle_for_reader.emit(&event_name_owned, ExportProgress {
+                        percent: 100.0, frame, fps, speed, done: true,
+                    });
+                }
+                _ => {}
+            }
+        }
+    });
+
+    // Drain stderr fully in parallel with progress so callers still get FFmpeg's
+    // error output on failure and the pipe never backs up and blocks the child.
+    let mut stderr_buf = Vec::new();
+    let _ = stderr_pipe.read_to_end(&mut stderr_buf);
+
+    let status = {
+        let mut guard = EXPORT_CHILD.lock().unwrap();
+        match guard.as_mut() {
+            Some(child) => child.wait().map_err(|e| format!("Failed to wait for FFmpeg: {}", e))?,
+            // Cleared out from under us: cancel_export() killed it.
+            None => return Err("Export was cancelled".to_string()),
+        }
+    };
+    EXPORT_CHILD.lock().unwrap().take();
+
+    let _ = reader_thread.join();
+
+    Ok(std::process::Output {
+        status,
+        stdout: Vec::new(),
+        stderr: stderr_buf,
+    })
+}
+
+/// Kill the currently tracked export, if any. Sends `q` on stdin first for FFmpeg's
+/// own clean shutdown (so a partially-written moov atom etc. isn't left dangling),
+/// then force-kills if it doesn't exit quickly.
+pub fn cancel_export() -> Result<(), String> {
+    let mut guard = EXPORT_CHILD.lock().unwrap();
+    let mut child = guard.take().ok_or_else(|| "No export in progress".to_string())?;
+    drop(guard);
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        let _ = stdin.write_all(b"q");
+        let _ = stdin.flush();
+    }
+
+    for _ in 0..15 {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) => thread::sleep(Duration::from_millis(200)),
+            Err(_) => break,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+    Ok(())
+}
@@ -0,0 +1,26 @@
+/// FFmpeg args that drive SVT-AV1's native film-grain synthesis at `strength`
+/// (0-64, rescaled onto SVT-AV1's own 0-50 `film-grain` level). Empty when
+/// `strength` is 0.
+pub fn svtav1_grain_args(strength: u32) -> Vec<String> {
+    if strength == 0 {
+        return Vec::new();
+    }
+    let level = (strength.min(64) as f64 / 64.0 * 50.0).round() as u32;
+    vec![
+        "-svtav1-params".to_string(),
+        format!("film-grain={}:film-grain-denoise=0", level),
+    ]
+}
+
+/// `-vf noise=...` fallback for encoders with no native grain-synthesis
+/// passthrough (libx264, libx265, libvpx-vp9): injects temporally-varying
+/// uniform noise on every plane (luma and chroma) at an amplitude derived from `strength`,
+/// applied as a filter-chain step rather than at the bitstream level. Returns
+/// `None` when `strength` is 0.
+pub fn noise_filter_fallback(strength: u32) -> Option<String> {
+    if strength == 0 {
+        return None;
+    }
+    let alls = ((strength.min(64) as f64 / 64.0 * 40.0).round() as u32).max(1);
+    Some(format!("noise=alls={}:allf=t+u", alls))
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+/// A single step in a declarative export filtergraph. Each variant maps to an
+/// FFmpeg `-vf`/`-filter_complex` fragment; a full pipeline is a `Vec<Filter>`
+/// applied in order to one or more inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Filter {
+    FadeIn { start: f64, duration: f64 },
+    FadeOut { start: f64, duration: f64 },
+    Trim { start: f64, end: f64 },
+    Concat { inputs: Vec<String> },
+}
+
+/// One source file in an export pipeline, with its own optional seek/duration window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportInput {
+    pub file_path: String,
+    pub seek: Option<f64>,     // -ss
+    pub duration: Option<f64>, // -t
+}
+
+/// Build the `-vf` filter string for the non-concat filters (fade in/out, trim)
+/// that apply to a single input stream, in the order given.
+fn build_vf_string(filters: &[Filter]) -> Option<String> {
+    let mut parts = Vec::new();
+    for filter in filters {
+        match filter {
+            Filter::FadeIn { start, duration } => {
+                parts.push(format!("fade=t=in:st={:.3}:d={:.3}", start, duration));
+            }
+            Filter::FadeOut { start, duration } => {
+                parts.push(format!("fade=t=out:st={:.3}:d={:.3}", start, duration));
+            }
+            Filter::Trim { start, end } => {
+                parts.push(format!("trim=start={:.3}:end={:.3},setpts=PTS-STARTPTS", start, end));
+            }
+            Filter::Concat { .. } => {} // Handled separately, not part of a per-input -vf chain
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(","))
+    }
+}
+
+/// Run a declarative filter pipeline over `inputs`, writing the result to `output_path`.
+///
+/// If the pipeline contains a `Concat` step and the caller sets `use_concat_demuxer`
+/// (inputs are not inspected; they must share the same codec parameters), the concat *demuxer* (`-f concat -safe 0`) is used for a lossless,
+/// re-encode-free join. Otherwise clips are stitched via `-filter_complex` concat,
+/// which re-encodes but tolerates mismatched codecs/resolutions.
+pub fn run_export_pipeline(
+    ffmpeg_path: &str,
+    inputs: &[ExportInput],
+    filters: &[Filter],
+    output_path: &str,
+    use_concat_demuxer: bool,
+) -> Result<String, String> {
+    if inputs.is_empty() {
+        return Err("No inputs provided to export pipeline".to_string());
+    }
+
+    let has_concat = filters.iter().any(|f| matches!(f, Filter::Concat { .. }));
+
+    if has_concat && use_concat_demuxer {
+        return run_concat_demuxer(ffmpeg_path, inputs, output_path);
+    }
+
+    let mut cmd = crate::hidden_command(ffmpeg_path);
+    cmd.arg("-y");
+
+    for input in inputs {
+        if let Some(seek) = input.seek {
+            cmd.arg("-ss").arg(format!("{:.3}", seek));
+        }
+        if let Some(duration) = input.duration {
+            cmd.arg("-t").arg(format!("{:.3}", duration));
+        }
+        cmd.arg("-i").arg(&input.file_path);
+    }
+
+    if has_concat && inputs.len() > 1 {
+        // Build a filter_complex concat across all inputs, applying the per-input
+        // fade/trim filters to each stream before the concat node.
+        let per_input_vf = build_vf_string(filters);
+        let mut filter_complex = String::new();
+        for (i, _) in inputs.iter().enumerate() {
+            if let Some(ref vf) = per_input_vf {
+                filter_complex.push_str(&format!("[{}:v]{}[v{}];", i, vf, i));
+            } else {
+                filter_complex.push_str(&format!("[{}:v]copy[v{}];", i, i));
+            }
+        }
+        for i in 0..inputs.len() {
+            filter_complex.push_str(&format!("[v{}][{}:a]", i, i));
+        }
+        filter_complex.push_str(&format!("concat=n={}:v=1:a=1[vout][aout]", inputs.len()));
+
+        cmd.arg("-filter_complex").arg(&filter_complex);
+        cmd.arg("-map").arg("[vout]");
+        cmd.arg("-map").arg("[aout]");
+    } else if let Some(vf) = build_vf_string(filters) {
+        cmd.arg("-vf").arg(vf);
+    }
+
+    cmd.arg("-c:v").arg("libx264");
+    cmd.arg("-c:a").arg("aac");
+    cmd.arg("-pix_fmt").arg("yuv420p");
+    cmd.arg("-movflags").arg("faststart");
+    cmd.arg(output_path);
+    cmd.arg("-hide_banner");
+    cmd.arg("-loglevel").arg("error");
+
+    let output = cmd.output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if output.status.success() {
+        Ok(output_path.to_string())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        Err(format!("FFmpeg export pipeline failed: {}", error))
+    }
+}
+
+/// Concat same-codec clips with `-c copy` via the concat demuxer. Fast and lossless,
+/// but requires all inputs to already share codec/resolution/timebase.
+fn run_concat_demuxer(ffmpeg_path: &str, inputs: &[ExportInput], output_path: &str) -> Result<String, String> {
+    let concat_file = std::env::temp_dir().join(format!(
+        "vibeclips_concat_{}.txt",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+
+    let concat_content: String = inputs
+        .iter()
+        .map(|i| format!("file '{}'\n", i.file_path.replace('\\', "/")))
+        .collect();
+    std::fs::write(&concat_file, concat_content)
+        .map_err(|e| format!("Failed to write concat file: {}", e))?;
+
+    let output = crate::hidden_command(ffmpeg_path)
+        .arg("-y")
+        .arg("-f").arg("concat")
+        .arg("-safe").arg("0")
+        .arg("-i").arg(&concat_file)
+        .arg("-c").arg("copy")
+        .arg(output_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    let _ = std::fs::remove_file(&concat_file);
+
+    if output.status.success() {
+        Ok(output_path.to_string())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Concat demuxer failed: {}", error))
+    }
+}
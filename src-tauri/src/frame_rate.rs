@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// An exact frame rate as a numerator/denominator pair (e.g. `24000/1001` for
+/// 23.976fps), carried through the export pipeline instead of a lossy float so
+/// NTSC rates round-trip exactly through FFmpeg's `-r`/`fps=` arguments rather
+/// than drifting out of sync across a long concat.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrameRate {
+    pub num: u32,
+    pub den: u32,
+}
+
+impl FrameRate {
+    pub fn as_f64(&self) -> f64 {
+        if self.den == 0 { 0.0 } else { self.num as f64 / self.den as f64 }
+    }
+
+    /// The `num/den` form FFmpeg's `-r` and `fps=` both accept directly.
+    pub fn as_ffmpeg_arg(&self) -> String {
+        format!("{}/{}", self.num, self.den)
+    }
+}
+
+/// Parse ffprobe's `r_frame_rate` output (`"24000/1001"`, or a bare integer
+/// like `"30"` for whole-number rates) into an exact rational.
+fn parse_r_frame_rate(raw: &str) -> Option<FrameRate> {
+    let raw = raw.trim();
+    match raw.split_once('/') {
+        Some((num, den)) => Some(FrameRate { num: num.parse().ok()?, den: den.parse().ok()? }),
+        None => Some(FrameRate { num: raw.parse().ok()?, den: 1 }),
+    }
+}
+
+/// Probe a source's exact frame rate via `ffprobe ... r_frame_rate`. Returns
+/// `None` if ffprobe can't be run or the stream doesn't report a usable one.
+pub fn probe_frame_rate(ffprobe_path: &str, input_path: &str) -> Option<FrameRate> {
+    let output = Command::new(ffprobe_path)
+        .arg("-v").arg("error")
+        .arg("-select_streams").arg("v:0")
+        .arg("-show_entries").arg("stream=r_frame_rate")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let rate = parse_r_frame_rate(&String::from_utf8_lossy(&output.stdout))?;
+    if rate.num == 0 || rate.den == 0 { None } else { Some(rate) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_r_frame_rate_fraction() {
+        let rate = parse_r_frame_rate("24000/1001").unwrap();
+        assert_eq!(rate.num, 24000);
+        assert_eq!(rate.den, 1001);
+        assert!((rate.as_f64() - 23.976).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_r_frame_rate_whole_number() {
+        let rate = parse_r_frame_rate("30").unwrap();
+        assert_eq!(rate.num, 30);
+        assert_eq!(rate.den, 1);
+        assert_eq!(rate.as_f64(), 30.0);
+    }
+
+    #[test]
+    fn test_parse_r_frame_rate_trims_whitespace() {
+        let rate = parse_r_frame_rate("  60/1\n").unwrap();
+        assert_eq!(rate.num, 60);
+        assert_eq!(rate.den, 1);
+    }
+
+    #[test]
+    fn test_parse_r_frame_rate_rejects_garbage() {
+        assert!(parse_r_frame_rate("not-a-rate").is_none());
+    }
+
+    #[test]
+    fn test_as_ffmpeg_arg() {
+        let rate = FrameRate { num: 24000, den: 1001 };
+        assert_eq!(rate.as_ffmpeg_arg(), "24000/1001");
+    }
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Explicit user HDR choice for an export, overriding whatever
+/// `probe_color_properties` reads off the source when set. `Auto` (the
+/// default) trusts the probed color transfer/primaries/space instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum HdrMode {
+    #[default]
+    Auto,
+    ForceSdr,
+    ForceHdr,
+}
+
+/// Color metadata read off a source file's first video stream via `ffprobe`.
+/// Fields are `None` when FFmpeg itself didn't tag the source.
+#[derive(Debug, Clone, Default)]
+pub struct ColorProps {
+    pub transfer: Option<String>,
+    pub primaries: Option<String>,
+    pub space: Option<String>,
+}
+
+impl ColorProps {
+    /// PQ (`smpte2084`) or HLG (`arib-std-b67`) transfer characteristics - the
+    /// two HDR transfer functions FFmpeg and most decoders recognize.
+    pub fn is_hdr(&self) -> bool {
+        matches!(self.transfer.as_deref(), Some("smpte2084") | Some("arib-std-b67"))
+    }
+}
+
+/// Run `ffprobe` against `input_path`'s first video stream for the three
+/// properties that distinguish an HDR (PQ/HLG) source from SDR: transfer
+/// characteristics, color primaries, and matrix coefficients (`color_space`
+/// in ffprobe's naming). Missing/unparseable fields are left `None` rather
+/// than erroring, since plenty of sources simply don't tag this at all.
+pub fn probe_color_properties(ffprobe_path: &str, input_path: &str) -> ColorProps {
+    let output = Command::new(ffprobe_path)
+        .arg("-v").arg("error")
+        .arg("-select_streams").arg("v:0")
+        .arg("-show_entries").arg("stream=color_transfer,color_primaries,color_space")
+        .arg("-of").arg("default=noprint_wrappers=1")
+        .arg(input_path)
+        .output();
+
+    let Ok(output) = output else {
+        return ColorProps::default();
+    };
+    if !output.status.success() {
+        return ColorProps::default();
+    }
+
+    let mut props = ColorProps::default();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() || value == "unknown" || value == "N/A" {
+            continue;
+        }
+        match key {
+            "color_transfer" => props.transfer = Some(value.to_string()),
+            "color_primaries" => props.primaries = Some(value.to_string()),
+            "color_space" => props.space = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    props
+}
+
+/// Everything an encode command needs to carry an HDR (or SDR) source through
+/// without crushing it: the pixel format the transfer needs (10-bit for HDR,
+/// so PQ/HLG don't lose precision to 8-bit banding) and the
+/// `-color_primaries`/`-color_trc`/`-colorspace` tags FFmpeg needs to stamp
+/// the same metadata onto the encoded output.
+pub struct HdrDecision {
+    pub pix_fmt: &'static str,
+    pub color_args: Vec<String>,
+}
+
+/// Resolve the pixel format/color tagging to use for one clip's encode. An
+/// explicit `mode` always wins over the probe (so a user intentionally
+/// forcing HDR on a source FFmpeg didn't tag still gets correct 10-bit +
+/// PQ/HLG tagging, and forcing SDR always crushes to 8-bit regardless of what
+/// the source claims); `HdrMode::Auto` falls back to whatever `probe` detected.
+pub fn resolve(mode: HdrMode, probe: &ColorProps) -> HdrDecision {
+    let is_hdr = match mode {
+        HdrMode::ForceSdr => false,
+        HdrMode::ForceHdr => true,
+        HdrMode::Auto => probe.is_hdr(),
+    };
+
+    if !is_hdr {
+        return HdrDecision {
+            pix_fmt: "yuv420p",
+            color_args: Vec::new(),
+        };
+    }
+
+    // Rec.2020 PQ is the standard fallback triplet for a source that's being
+    // treated as HDR but that FFmpeg couldn't (or didn't) tag itself.
+    let transfer = probe.transfer.clone().unwrap_or_else(|| "smpte2084".to_string());
+    let primaries = probe.primaries.clone().unwrap_or_else(|| "bt2020".to_string());
+    let space = probe.space.clone().unwrap_or_else(|| "bt2020nc".to_string());
+
+    HdrDecision {
+        pix_fmt: "yuv420p10le",
+        color_args: vec![
+            "-color_primaries".to_string(), primaries,
+            "-color_trc".to_string(), transfer,
+            "-colorspace".to_string(), space,
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_hdr_recognizes_pq_and_hlg() {
+        let pq = ColorProps { transfer: Some("smpte2084".to_string()), ..Default::default() };
+        let hlg = ColorProps { transfer: Some("arib-std-b67".to_string()), ..Default::default() };
+        let sdr = ColorProps { transfer: Some("bt709".to_string()), ..Default::default() };
+        assert!(pq.is_hdr());
+        assert!(hlg.is_hdr());
+        assert!(!sdr.is_hdr());
+        assert!(!ColorProps::default().is_hdr());
+    }
+
+    #[test]
+    fn test_resolve_force_sdr_ignores_probe() {
+        let probe = ColorProps { transfer: Some("smpte2084".to_string()), ..Default::default() };
+        let decision = resolve(HdrMode::ForceSdr, &probe);
+        assert_eq!(decision.pix_fmt, "yuv420p");
+        assert!(decision.color_args.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_auto_sdr_source() {
+        let probe = ColorProps { transfer: Some("bt709".to_string()), ..Default::default() };
+        let decision = resolve(HdrMode::Auto, &probe);
+        assert_eq!(decision.pix_fmt, "yuv420p");
+        assert!(decision.color_args.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_auto_hdr_source_preserves_tags() {
+        let probe = ColorProps {
+            transfer: Some("smpte2084".to_string()),
+            primaries: Some("bt2020".to_string()),
+            space: Some("bt2020nc".to_string()),
+        };
+        let decision = resolve(HdrMode::Auto, &probe);
+        assert_eq!(decision.pix_fmt, "yuv420p10le");
+        assert_eq!(
+            decision.color_args,
+            vec![
+                "-color_primaries".to_string(), "bt2020".to_string(),
+                "-color_trc".to_string(), "smpte2084".to_string(),
+                "-colorspace".to_string(), "bt2020nc".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_force_hdr_untagged_source_uses_rec2020_pq_fallback() {
+        let decision = resolve(HdrMode::ForceHdr, &ColorProps::default());
+        assert_eq!(decision.pix_fmt, "yuv420p10le");
+        assert_eq!(
+            decision.color_args,
+            vec![
+                "-color_primaries".to_string(), "bt2020".to_string(),
+                "-color_trc".to_string(), "smpte2084".to_string(),
+                "-colorspace".to_string(), "bt2020nc".to_string(),
+            ]
+        );
+    }
+}
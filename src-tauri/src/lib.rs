@@ -1,4 +1,5 @@
 use tauri::Manager;
+use tauri::Emitter;
 use serde::{Deserialize, Serialize};
 use std::process::{Command, Stdio};
 // transcription module is available via mod transcription above
@@ -9,12 +10,26 @@ use std::os::windows::process::CommandExt;
 mod screen_capture;
 mod audio_capture;
 mod transcription;
+mod filter_pipeline;
+mod chunked_export;
+mod vmaf;
+mod encoder_backend;
+mod export_progress;
+mod streaming_export;
+mod output_codec;
+mod clip_trim_pool;
+mod film_grain;
+mod subtitle_style;
+mod speed_ramp;
+mod streaming_format;
+mod hdr;
+mod frame_rate;
 
 const KEYRING_SERVICE: &str = "com.vibeclips.app";
 const KEYRING_USERNAME: &str = "openai_api_key";
 
 // Helper function to get bundled FFmpeg path
-fn get_bundled_ffmpeg_path(app_handle: &tauri::AppHandle, program: &str) -> Option<std::path::PathBuf> {
+pub(crate) fn get_bundled_ffmpeg_path(app_handle: &tauri::AppHandle, program: &str) -> Option<std::path::PathBuf> {
     if let Ok(resource_dir) = app_handle.path().resource_dir() {
         #[cfg(target_os = "windows")]
         let binary_name = format!("{}.exe", program);
@@ -31,7 +46,7 @@ fn get_bundled_ffmpeg_path(app_handle: &tauri::AppHandle, program: &str) -> Opti
 }
 
 // Helper function to find ffmpeg/ffprobe executable
-fn find_ffmpeg_binary(app_handle: Option<&tauri::AppHandle>, program: &str) -> String {
+pub(crate) fn find_ffmpeg_binary(app_handle: Option<&tauri::AppHandle>, program: &str) -> String {
     // First, try bundled binaries if app_handle is available
     if let Some(handle) = app_handle {
         if let Some(bundled_path) = get_bundled_ffmpeg_path(handle, program) {
@@ -69,10 +84,12 @@ fn find_ffmpeg_binary(app_handle: Option<&tauri::AppHandle>, program: &str) -> S
     program.to_string()
 }
 
-// Helper function to create a Command that won't show a console window on Windows
-fn create_hidden_command(app_handle: Option<&tauri::AppHandle>, program: &str) -> Command {
-    let ffmpeg_path = find_ffmpeg_binary(app_handle, program);
-    let mut cmd = Command::new(ffmpeg_path);
+// Wrap an already-resolved binary path in a Command that won't show a console
+// window on Windows, without re-running `find_ffmpeg_binary`'s PATH/bundled
+// resolution - for callers (like `encoder_backend`'s probing) that resolved
+// the binary path themselves and just need the hidden-window behavior.
+pub(crate) fn hidden_command(program: &str) -> Command {
+    let mut cmd = Command::new(program);
     #[cfg(target_os = "windows")]
     {
         const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -81,11 +98,18 @@ fn create_hidden_command(app_handle: Option<&tauri::AppHandle>, program: &str) -
     cmd
 }
 
+// Helper function to create a Command that won't show a console window on Windows
+pub(crate) fn create_hidden_command(app_handle: Option<&tauri::AppHandle>, program: &str) -> Command {
+    let ffmpeg_path = find_ffmpeg_binary(app_handle, program);
+    hidden_command(&ffmpeg_path)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ClipFilters {
     brightness: Option<i32>,  // -100 to 100
     contrast: Option<i32>,    // -100 to 100
     saturation: Option<i32>,  // -100 to 100
+    grain_strength: Option<u32>, // 0-64, synthetic film-grain strength (see `film_grain`)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,6 +120,9 @@ struct ClipData {
     start_time: f64,
     track: i32, // Track number: 0 = main video, 1 = overlay 1, 2 = overlay 2
     filters: Option<ClipFilters>, // Optional filters applied to clip
+    // Fast-forward regions on this clip's own trimmed timeline (start, end, factor),
+    // e.g. [(5.0, 7.0, 3.0)] plays seconds 5-7 at 3x speed. See `speed_ramp`.
+    speed_segments: Option<Vec<(f64, f64, f64)>>,
 }
 
 #[tauri::command]
@@ -282,8 +309,18 @@ fn test_ffmpeg(app_handle: tauri::AppHandle) -> Result<String, String> {
 }
 
 #[tauri::command]
-fn start_screen_recording_async(output_path: String) -> Result<String, String> {
-    screen_capture::start_screen_recording_process(output_path)
+fn start_screen_recording_async(
+    output_path: String,
+    encoder: Option<screen_capture::VideoEncoder>,
+    source_id: Option<String>,
+    region: Option<screen_capture::CaptureRegion>,
+) -> Result<String, String> {
+    screen_capture::start_screen_recording_process_full(
+        output_path,
+        encoder.unwrap_or(screen_capture::VideoEncoder::Libx264),
+        source_id,
+        region,
+    )
 }
 
 #[tauri::command]
@@ -296,6 +333,100 @@ fn get_recording_status() -> Result<bool, String> {
     screen_capture::get_recording_status()
 }
 
+#[tauri::command]
+fn start_replay_buffer(buffer_seconds: u32) -> Result<String, String> {
+    screen_capture::start_replay_buffer(buffer_seconds)
+}
+
+#[tauri::command]
+fn stop_replay_buffer() -> Result<String, String> {
+    screen_capture::stop_replay_buffer()
+}
+
+#[tauri::command]
+fn save_replay(output_path: String, seconds: u32) -> Result<String, String> {
+    screen_capture::save_replay(output_path, seconds)
+}
+
+#[tauri::command]
+fn start_caption_chunking(
+    chunk_seconds: f64,
+    whisper_cpp_binary: Option<String>,
+    whisper_cpp_model: Option<String>,
+) -> Result<(), String> {
+    use std::sync::Arc;
+    let transcriber: Option<Arc<dyn transcription::Transcriber>> =
+        match (whisper_cpp_binary, whisper_cpp_model) {
+            (Some(binary), Some(model)) => Some(Arc::new(transcription::WhisperCppTranscriber::new(binary, model))),
+            _ => None,
+        };
+    audio_capture::start_caption_chunking(chunk_seconds, transcriber)
+}
+
+#[tauri::command]
+fn stop_caption_chunking(video_output_path: String) -> Result<Option<String>, String> {
+    audio_capture::stop_caption_chunking(&video_output_path)
+}
+
+#[tauri::command]
+fn start_dual_audio_capture(
+    output_path: String,
+    mic_gain: Option<f32>,
+    system_gain: Option<f32>,
+) -> Result<(), String> {
+    audio_capture::start_dual_audio_capture(output_path, mic_gain.unwrap_or(1.0), system_gain.unwrap_or(1.0))
+}
+
+#[tauri::command]
+fn start_audio_capture_opus(output_path: String, bitrate: Option<i32>) -> Result<(), String> {
+    audio_capture::start_audio_capture_opus(output_path, bitrate.unwrap_or(32_000))
+}
+
+#[tauri::command]
+fn get_audio_levels() -> audio_capture::AudioLevels {
+    audio_capture::get_audio_levels()
+}
+
+#[tauri::command]
+fn start_streaming_async(
+    target: screen_capture::OutputTarget,
+    encoder: Option<screen_capture::VideoEncoder>,
+) -> Result<String, String> {
+    screen_capture::start_streaming_process(target, encoder.unwrap_or(screen_capture::VideoEncoder::Libx264))
+}
+
+#[tauri::command]
+fn stop_streaming_async() -> Result<String, String> {
+    screen_capture::stop_streaming_process()
+}
+
+#[tauri::command]
+fn run_export_pipeline(
+    app_handle: tauri::AppHandle,
+    inputs: Vec<filter_pipeline::ExportInput>,
+    filters: Vec<filter_pipeline::Filter>,
+    output_path: String,
+    use_concat_demuxer: Option<bool>,
+) -> Result<String, String> {
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+    filter_pipeline::run_export_pipeline(&ffmpeg_path, &inputs, &filters, &output_path, use_concat_demuxer.unwrap_or(true))
+}
+
+#[tauri::command]
+fn split_recording_into_scenes(
+    video_path: String,
+    output_dir: String,
+    threshold: Option<f64>,
+    min_clip_secs: Option<f64>,
+) -> Result<Vec<String>, String> {
+    screen_capture::split_into_scenes(
+        &video_path,
+        &output_dir,
+        threshold.unwrap_or(0.3),
+        min_clip_secs.unwrap_or(1.0),
+    )
+}
+
 #[tauri::command]
 fn mux_video_audio(app_handle: tauri::AppHandle, video_path: String, audio_path: String, output_path: String) -> Result<String, String> {
     let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
@@ -323,25 +454,165 @@ fn mux_video_audio(app_handle: tauri::AppHandle, video_path: String, audio_path:
 }
 
 #[tauri::command]
-fn convert_webm_to_mp4(app_handle: tauri::AppHandle, input_path: String, output_path: String) -> Result<String, String> {
-    let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-    cmd.arg("-y");
-    cmd.arg("-i").arg(&input_path);
-    cmd.arg("-c:v").arg("libx264");
-    cmd.arg("-preset").arg("fast");
-    cmd.arg("-crf").arg("23");
-    cmd.arg("-c:a").arg("aac");
-    cmd.arg("-b:a").arg("192k");
-    cmd.arg("-movflags").arg("faststart");
-    cmd.arg(&output_path);
-    cmd.arg("-hide_banner");
-    cmd.arg("-loglevel").arg("error");
-    cmd.stdout(Stdio::null());
-    cmd.stderr(Stdio::piped());
-    
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?;
-    
+fn list_encoders(app_handle: tauri::AppHandle) -> Vec<encoder_backend::EncoderBackend> {
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+    encoder_backend::list_available_backends(&ffmpeg_path)
+}
+
+#[tauri::command]
+fn cancel_export() -> Result<(), String> {
+    export_progress::cancel_export()
+}
+
+#[tauri::command]
+fn export_streaming(
+    app_handle: tauri::AppHandle,
+    input_path: String,
+    output_dir: String,
+    segment_secs: Option<f64>,
+    variants: Vec<streaming_export::StreamVariant>,
+    preset: Option<String>,
+) -> Result<String, String> {
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+    let ffprobe_path = find_ffmpeg_binary(Some(&app_handle), "ffprobe");
+    streaming_export::export_hls(
+        &ffmpeg_path,
+        &ffprobe_path,
+        &input_path,
+        &output_dir,
+        segment_secs,
+        &variants,
+        &preset.unwrap_or_else(|| "fast".to_string()),
+    )
+}
+
+/// Composite `clips` exactly like `export_video` (same track/overlay/subtitle/
+/// HDR handling, via `export_video_blocking`), then hand the composited result
+/// to `streaming_export::export_hls` to segment into fragmented-MP4 HLS: an
+/// `init.mp4` + numbered `.m4s` segments + `stream.m3u8` per rendition, plus a
+/// `master.m3u8` tying the ladder together. `variants` defaults to the source
+/// resolution plus up to two downscaled renditions when not given. Returns
+/// the path to the master playlist.
+#[tauri::command]
+async fn export_hls(
+    app_handle: tauri::AppHandle,
+    clips: Vec<ClipData>,
+    output_dir: String,
+    width: u32,
+    height: u32,
+    crf: String,
+    preset: String,
+    overlay_positions: Option<OverlayPositions>,
+    #[allow(non_snake_case)] subtitleSrtPath: Option<String>,
+    workers: Option<usize>,
+    target_vmaf: Option<f64>,
+    encoder: Option<encoder_backend::EncoderBackend>,
+    subtitle_style_preset: Option<subtitle_style::StylePreset>,
+    subtitle_karaoke: Option<bool>,
+    hdr_mode: Option<hdr::HdrMode>,
+    segment_secs: Option<f64>,
+    variants: Option<Vec<streaming_export::StreamVariant>>,
+) -> Result<String, String> {
+    if clips.is_empty() {
+        return Err("No clips to export".to_string());
+    }
+
+    let mut sorted_clips = clips.clone();
+    sorted_clips.sort_by(|a, b| a.start_time.partial_cmp(&b.start_time).unwrap());
+
+    let temp_dir = std::env::temp_dir();
+    let intermediate_path = temp_dir.join(format!(
+        "vibeclips_hls_source_{}.mp4",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    let intermediate_path_str = intermediate_path.to_string_lossy().to_string();
+
+    let app_handle_clone = app_handle.clone();
+    let intermediate_for_blocking = intermediate_path_str.clone();
+
+    tokio::task::spawn_blocking(move || {
+        export_video_blocking(
+            app_handle_clone,
+            sorted_clips,
+            intermediate_for_blocking,
+            width,
+            height,
+            crf,
+            preset,
+            overlay_positions,
+            subtitleSrtPath,
+            workers,
+            target_vmaf,
+            encoder,
+            Some(output_codec::OutputCodec::Libx264),
+            None,
+            subtitle_style_preset,
+            subtitle_karaoke,
+            None,
+            None,
+            hdr_mode,
+            None,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+    let ffprobe_path = find_ffmpeg_binary(Some(&app_handle), "ffprobe");
+    let variants = variants.unwrap_or_else(|| streaming_export::default_variant_ladder(width, height, 6000));
+
+    let result = streaming_export::export_hls(
+        &ffmpeg_path,
+        &ffprobe_path,
+        &intermediate_path_str,
+        &output_dir,
+        segment_secs,
+        &variants,
+        "fast",
+    );
+
+    let _ = std::fs::remove_file(&intermediate_path_str);
+    result
+}
+
+#[tauri::command]
+fn convert_webm_to_mp4(
+    app_handle: tauri::AppHandle,
+    input_path: String,
+    output_path: String,
+    encoder: Option<encoder_backend::EncoderBackend>,
+) -> Result<String, String> {
+    let requested = encoder.unwrap_or(encoder_backend::EncoderBackend::Auto);
+    let total_duration = get_video_duration_from_file(app_handle.clone(), input_path.clone()).unwrap_or(0.0);
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+
+    let output = encoder_backend::run_with_hardware_fallback(requested, "23", "fast", &ffmpeg_path, |plan| {
+        let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
+        cmd.arg("-y");
+        for arg in &plan.input_args {
+            cmd.arg(arg);
+        }
+        cmd.arg("-i").arg(&input_path);
+
+        if let Some(suffix) = plan.filter_suffix {
+            cmd.arg("-vf").arg(suffix);
+        }
+
+        for arg in &plan.codec_args {
+            cmd.arg(arg);
+        }
+        cmd.arg("-c:a").arg("aac");
+        cmd.arg("-b:a").arg("192k");
+        cmd.arg("-movflags").arg("faststart");
+        cmd.arg(&output_path);
+        cmd.arg("-hide_banner");
+
+        export_progress::run_with_progress(cmd, &app_handle, total_duration, "export-progress")
+    })?;
+
     if output.status.success() {
         // Clean up the WebM file
         let _ = std::fs::remove_file(&input_path);
@@ -376,6 +647,7 @@ fn composite_pip_video(
     audio_options: AudioOptions,
     output_path: String,
     screen_start_offset: Option<f64>, // Seconds to delay screen audio/video
+    encoder: Option<encoder_backend::EncoderBackend>,
 ) -> Result<String, String> {
     // First, probe the input files to see which streams they actually have
     let screen_has_audio = check_has_audio_stream(&screen_path);
@@ -414,98 +686,96 @@ fn composite_pip_video(
         }
     };
 
-    let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-    cmd.arg("-y");
-    cmd.arg("-i").arg(&screen_path);    // Input 0: screen recording (master timeline)
-    cmd.arg("-i").arg(&webcam_path);    // Input 1: webcam recording
-    
-    // Use screen recording as master timeline
-    cmd.arg("-map_metadata").arg("0");
-    cmd.arg("-fflags").arg("+genpts");
-    
     let mut delay_offset = screen_start_offset.unwrap_or(0.0);
-    
+
     // Add a small buffer (100ms) to account for webcam processing latency
     // Webcams typically have 50-150ms inherent delay compared to screen capture
     delay_offset += 0.100;
-    
+
     println!("Compositing with screen delay offset: {:.3}s (includes 100ms webcam latency buffer)", delay_offset);
-    
-    // Ensure proper A/V sync
-    cmd.arg("-fps_mode").arg("vfr");
-    
+
     // Handle different audio combinations WITH PROPER DELAY
     // Note: aresample=async=1 is built into the filter_complex to fix sync issues
-    if use_screen_audio && use_webcam_audio {
+    let (filter_complex, has_audio_out) = if use_screen_audio && use_webcam_audio {
         // Both system audio and mic audio
         // Always apply delay since we now always have at least the 100ms buffer
         let delay_ms = (delay_offset * 1000.0) as i32;
         let screen_audio_filter = format!("[0:a]adelay={}|{},aresample=async=1,apad[a0]", delay_ms, delay_ms);
-        
-        let filter_complex = format!(
+
+        (format!(
             "[1:v]scale={}:{}[pip];[0:v][pip]overlay={}[vout];{};[1:a]aresample=async=1,apad[a1];[a0][a1]amix=inputs=2:duration=longest[aout]",
             pip_width, pip_height, overlay_position, screen_audio_filter
-        );
-        cmd.arg("-filter_complex").arg(&filter_complex);
-        cmd.arg("-map").arg("[vout]");
-        cmd.arg("-map").arg("[aout]");
+        ), true)
     } else if use_screen_audio {
         // System audio only - always apply delay (includes webcam latency buffer)
         let delay_ms = (delay_offset * 1000.0) as i32;
-        let filter_complex = format!(
+        (format!(
             "[1:v]scale={}:{}[pip];[0:v][pip]overlay={}[vout];[0:a]adelay={}|{},aresample=async=1,apad[aout]",
             pip_width, pip_height, overlay_position, delay_ms, delay_ms
-        );
-        cmd.arg("-filter_complex").arg(&filter_complex);
-        cmd.arg("-map").arg("[vout]");
-        cmd.arg("-map").arg("[aout]");
+        ), true)
     } else if use_webcam_audio {
         // Mic audio only - pad to match video duration
-        let filter_complex = format!(
+        (format!(
             "[1:v]scale={}:{}[pip];[0:v][pip]overlay={}[vout];[1:a]aresample=async=1,apad[aout]",
             pip_width, pip_height, overlay_position
-        );
-        cmd.arg("-filter_complex").arg(&filter_complex);
-        cmd.arg("-map").arg("[vout]");
-        cmd.arg("-map").arg("[aout]");
+        ), true)
     } else {
         // No audio
-        let filter_complex = format!(
+        (format!(
             "[1:v]scale={}:{}[pip];[0:v][pip]overlay={}",
             pip_width, pip_height, overlay_position
-        );
+        ), false)
+    };
+
+    // Default to `Auto` (the first available hardware backend, else ultrafast
+    // libx264), retrying on software if the hardware encode process fails.
+    let requested_encoder = encoder.unwrap_or(encoder_backend::EncoderBackend::Auto);
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+    let output = encoder_backend::run_with_hardware_fallback(requested_encoder, "23", "ultrafast", &ffmpeg_path, |plan| {
+        let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
+        cmd.arg("-y");
+        cmd.arg("-i").arg(&screen_path);    // Input 0: screen recording (master timeline)
+        cmd.arg("-i").arg(&webcam_path);    // Input 1: webcam recording
+
+        // Use screen recording as master timeline
+        cmd.arg("-map_metadata").arg("0");
+        cmd.arg("-fflags").arg("+genpts");
+
+        // Ensure proper A/V sync
+        cmd.arg("-fps_mode").arg("vfr");
+
         cmd.arg("-filter_complex").arg(&filter_complex);
-    }
-    
-    // Video and audio encoding - use ultrafast for speed
-    cmd.arg("-c:v").arg("libx264");
-    cmd.arg("-preset").arg("ultrafast");
-    cmd.arg("-crf").arg("23");
-    
-    if use_screen_audio || use_webcam_audio {
-        cmd.arg("-c:a").arg("aac");
-        cmd.arg("-b:a").arg("192k");
-    }
-    
-    // Set output duration to match screen recording (master timeline)
-    if screen_duration > 0.0 {
-        cmd.arg("-t").arg(format!("{:.3}", screen_duration));
-    }
-    
-    // Don't use -shortest when we have delayed audio
-    // Instead, let the video determine the duration (via -t)
-    // The adelay filter will pad with silence, so audio won't be cut short
-    
-    cmd.arg("-movflags").arg("faststart");
-    cmd.arg(&output_path);
-    cmd.arg("-hide_banner");
-    cmd.arg("-loglevel").arg("info");
-    cmd.stdout(Stdio::null());
-    cmd.stderr(Stdio::piped());
-    
-    let output = cmd.output()
-        .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?;
-    
+        if has_audio_out {
+            cmd.arg("-map").arg("[vout]");
+            cmd.arg("-map").arg("[aout]");
+        }
+
+        for arg in &plan.codec_args {
+            cmd.arg(arg);
+        }
+
+        if use_screen_audio || use_webcam_audio {
+            cmd.arg("-c:a").arg("aac");
+            cmd.arg("-b:a").arg("192k");
+        }
+
+        // Set output duration to match screen recording (master timeline)
+        if screen_duration > 0.0 {
+            cmd.arg("-t").arg(format!("{:.3}", screen_duration));
+        }
+
+        // Don't use -shortest when we have delayed audio
+        // Instead, let the video determine the duration (via -t)
+        // The adelay filter will pad with silence, so audio won't be cut short
+
+        cmd.arg("-movflags").arg("faststart");
+        cmd.arg(&output_path);
+        cmd.arg("-hide_banner");
+
+        export_progress::run_with_progress(cmd, &app_handle, screen_duration, "export-progress")
+    })?;
+
+
     if output.status.success() {
         Ok(output_path)
     } else {
@@ -531,11 +801,10 @@ fn check_has_audio_stream(file_path: &str) -> bool {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct OverlayPositions {
-    track1: Option<String>, // "bottom-left" | "bottom-right" | "top-left" | "top-right" | "center"
-    track2: Option<String>,
-}
+// Per-track overlay position, keyed by track number (any track >= 1), e.g.
+// `{"1": "bottom-right", "3": "top-left"}`. Values are
+// "bottom-left" | "bottom-right" | "top-left" | "top-right" | "center".
+type OverlayPositions = std::collections::HashMap<i32, String>;
 
 #[tauri::command]
 async fn export_video(
@@ -548,6 +817,17 @@ async fn export_video(
     preset: String,
     overlay_positions: Option<OverlayPositions>,
     #[allow(non_snake_case)] subtitleSrtPath: Option<String>,
+    workers: Option<usize>,
+    target_vmaf: Option<f64>,
+    encoder: Option<encoder_backend::EncoderBackend>,
+    codec: Option<output_codec::OutputCodec>,
+    intermediate_quality: Option<bool>,
+    subtitle_style_preset: Option<subtitle_style::StylePreset>,
+    subtitle_karaoke: Option<bool>,
+    streaming_format: Option<streaming_format::StreamingFormat>,
+    segment_output: Option<bool>,
+    hdr_mode: Option<hdr::HdrMode>,
+    output_fps: Option<frame_rate::FrameRate>,
 ) -> Result<String, String> {
     // Write to log FIRST THING to verify function is called
     let log_path = std::path::Path::new("src-tauri/export_debug.log");
@@ -577,14 +857,35 @@ async fn export_video(
     log.push_str(&format!("Exporting {} clips to '{}'\n", clips.len(), outputPath));
     
     // Validate output path has a filename
-    if !outputPath.ends_with(".mp4") && !outputPath.ends_with(".mov") {
-        let error_msg = format!("Output path must end with .mp4 or .mov, got: '{}'", outputPath);
+    if !outputPath.ends_with(".mp4") && !outputPath.ends_with(".mov") && !outputPath.ends_with(".webm") {
+        let error_msg = format!("Output path must end with .mp4, .mov, or .webm, got: '{}'", outputPath);
         log.push_str(&format!("ERROR: {}\n", error_msg));
         let log_path = std::path::Path::new("src-tauri/export_debug.log");
         let _ = std::fs::write(log_path, &log);
         return Err(error_msg);
     }
-    
+
+    // Validate the requested codec can actually be muxed into this container
+    // before spawning any FFmpeg process, so a bad combination fails with a
+    // clear message instead of an opaque muxer error partway through export.
+    if let Some(codec) = codec {
+        if let Err(e) = output_codec::validate_codec_container(codec, &outputPath) {
+            log.push_str(&format!("ERROR: {}\n", e));
+            let log_path = std::path::Path::new("src-tauri/export_debug.log");
+            let _ = std::fs::write(log_path, &log);
+            return Err(e);
+        }
+    }
+
+    if let Err(e) =
+        streaming_format::validate_container(streaming_format.unwrap_or_default(), &outputPath)
+    {
+        log.push_str(&format!("ERROR: {}\n", e));
+        let log_path = std::path::Path::new("src-tauri/export_debug.log");
+        let _ = std::fs::write(log_path, &log);
+        return Err(e);
+    }
+
     log.push_str(&format!("Output path validated: {}\n", outputPath));
     
     for (i, clip) in clips.iter().enumerate() {
@@ -607,7 +908,7 @@ async fn export_video(
     
     // Run the export in a blocking task to avoid freezing the UI
     let result = tokio::task::spawn_blocking(move || {
-        export_video_blocking(app_handle_clone, sorted_clips, output_path_clone, width, height, crf, preset, overlay_positions_clone, subtitle_srt_path_clone)
+        export_video_blocking(app_handle_clone, sorted_clips, output_path_clone, width, height, crf, preset, overlay_positions_clone, subtitle_srt_path_clone, workers, target_vmaf, encoder, codec, intermediate_quality, subtitle_style_preset, subtitle_karaoke, streaming_format, segment_output, hdr_mode, output_fps)
     }).await.map_err(|e| format!("Task join error: {}", e))??;
     
     Ok(result)
@@ -658,6 +959,7 @@ async fn transcribe_clip(
     trim_start: f64,
     trim_end: f64,
     api_key: Option<String>, // Optional - will use keyring if not provided
+    word_timings: Option<bool>, // When true, use verbose_json for per-word karaoke timings
 ) -> Result<transcription::TranscriptionResponse, String> {
     use std::env;
     use keyring::Entry;
@@ -722,10 +1024,17 @@ async fn transcribe_clip(
     }
     
     // Transcribe using Whisper API
-    let result = transcription::transcribe_audio_whisper(
-        audio_path.to_str().unwrap(),
-        &final_api_key,
-    ).await?;
+    let result = if word_timings.unwrap_or(false) {
+        transcription::transcribe_audio_whisper_verbose(
+            audio_path.to_str().unwrap(),
+            &final_api_key,
+        ).await?
+    } else {
+        transcription::transcribe_audio_whisper(
+            audio_path.to_str().unwrap(),
+            &final_api_key,
+        ).await?
+    };
     
     // Clean up temp audio file
     let _ = std::fs::remove_file(&audio_path);
@@ -764,112 +1073,62 @@ fn build_eq_filter(filters: &Option<ClipFilters>) -> Option<String> {
     }
 }
 
-// Blocking export function that does the actual FFmpeg work
-fn calculate_overlay_pos(position: &str, base_w: u32, base_h: u32, overlay_w: u32, overlay_h: u32, padding: u32) -> (u32, u32) {
-    match position {
-        "bottom-left" => (padding, base_h - overlay_h - padding),
-        "bottom-right" => (base_w - overlay_w - padding, base_h - overlay_h - padding),
-        "top-left" => (padding, padding),
-        "top-right" => (base_w - overlay_w - padding, padding),
-        "center" => ((base_w - overlay_w) / 2, (base_h - overlay_h) / 2),
-        _ => (base_w - overlay_w - padding, base_h - overlay_h - padding), // default to bottom-right
-    }
+// Helper function to read the grain strength out of ClipFilters, consumed
+// alongside build_eq_filter wherever per-clip filters are applied.
+fn clip_grain_strength(filters: &Option<ClipFilters>) -> u32 {
+    filters.as_ref().and_then(|f| f.grain_strength).unwrap_or(0)
 }
 
-// Helper function to convert SRT to ASS format for better FFmpeg compatibility
-fn convert_srt_to_ass(srt_path: &str, _app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    use std::fs;
-    
-    println!("ðŸŽ¬ Processing subtitles from: {}", srt_path);
-    
-    // Verify source SRT exists
+// Build a styled `-vf`/`filter_complex` fragment for burning in `srt_path`'s
+// subtitles: reads the SRT, renders it to an ASS file via `subtitle_style`
+// (plain or word-level karaoke), writes it next to the working directory -
+// same relative-path workaround as the old raw-SRT copy, to dodge Windows
+// drive-letter colon issues in FFmpeg's filter syntax - and returns the
+// `ass=...` filter fragment pointing at it.
+//
+// `time_remap`, when given, is forwarded to `subtitle_style::build_ass_content`
+// to keep cues in sync with any `speed_ramp` segments on the timeline the
+// subtitles are being burned onto.
+fn prepare_subtitle_filter(
+    srt_path: &str,
+    style_preset: subtitle_style::StylePreset,
+    karaoke: bool,
+    time_remap: Option<&dyn Fn(f64) -> f64>,
+) -> Result<String, String> {
+    println!("ðŸŽ¬ Burning subtitles from: {}", srt_path);
+
     if !std::path::Path::new(srt_path).exists() {
         return Err(format!("Subtitle file not found: {}", srt_path));
     }
-    
-    // Read SRT content
-    let srt_content = fs::read_to_string(srt_path)
+
+    let srt_content = std::fs::read_to_string(srt_path)
         .map_err(|e| format!("Failed to read SRT: {}", e))?;
-    
-    println!("SRT content ({} bytes):\n{}", srt_content.len(), 
-        &srt_content.chars().take(300).collect::<String>());
-    
-    // Parse SRT and convert to ASS format
-    let temp_dir = std::env::temp_dir();
-    let ass_file = temp_dir.join("vibesubtitles.ass");
-    
-    println!("Converting SRT to ASS format...");
-    
-    // Simple SRT to ASS conversion
-    let ass_content = convert_srt_to_ass_content(&srt_content);
-    
-    fs::write(&ass_file, ass_content)
+    println!("SRT content ({} bytes)", srt_content.len());
+
+    let ass_content =
+        subtitle_style::build_ass_content(&srt_content, &style_preset.style(), karaoke, time_remap)?;
+
+    // WORKAROUND: write next to the working directory (no drive letter path issues)
+    let simple_ass = std::path::PathBuf::from("./temp_subtitles.ass");
+    std::fs::write(&simple_ass, ass_content)
         .map_err(|e| format!("Failed to write ASS file: {}", e))?;
-    
-    println!("âœ“ Converted to ASS: {:?}", ass_file);
-    
-    Ok(ass_file)
-}
+    println!("Wrote styled ASS to: {:?}", simple_ass);
 
-// Convert SRT content to ASS format
-fn convert_srt_to_ass_content(srt_content: &str) -> String {
-    let mut ass = String::from("[Script Info]\n");
-    ass.push_str("Title: VibeClips Subtitles\n");
-    ass.push_str("ScriptType: v4.00+\n\n");
-    ass.push_str("[V4+ Styles]\n");
-    ass.push_str("Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n");
-    ass.push_str("Style: Default,Arial,24,&Hffffff,&Hffffff,&H0,&H80000000,0,0,0,0,100,100,0,0,1,2,1,2,10,10,10,1\n\n");
-    ass.push_str("[Events]\n");
-    ass.push_str("Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n");
-    
-    // Parse SRT entries
-    let blocks: Vec<&str> = srt_content.split("\n\n").collect();
-    
-    for block in blocks {
-        let lines: Vec<&str> = block.trim().lines().collect();
-        if lines.len() < 3 {
-            continue;
-        }
-        
-        // Skip subtitle number (first line)
-        if let Some(time_line) = lines.get(1) {
-            // Parse time: "00:00:00,000 --> 00:00:02,799"
-            if let Some((start, end)) = parse_srt_time(time_line) {
-                // Get text (rest of lines)
-                let text = lines[2..].join("\\N");
-                
-                // Escape ASS special characters
-                let text_escaped = text
-                    .replace("\\", "\\\\")
-                    .replace("{", "\\{")
-                    .replace("}", "\\}");
-                
-                ass.push_str(&format!("Dialogue: 0,{},{}Default,,0,0,0,,{}\n", start, end, text_escaped));
-            }
-        }
-    }
-    
-    ass
+    let subtitle_filter = "ass=./temp_subtitles.ass".to_string();
+    println!("Subtitle filter: {}", subtitle_filter);
+    Ok(subtitle_filter)
 }
 
-// Parse SRT time format: "00:00:00,000 --> 00:00:02,799" to ASS format: "0:00:00.00"
-fn parse_srt_time(time_line: &str) -> Option<(String, String)> {
-    let parts: Vec<&str> = time_line.split(" --> ").collect();
-    if parts.len() != 2 {
-        return None;
+// Blocking export function that does the actual FFmpeg work
+fn calculate_overlay_pos(position: &str, base_w: u32, base_h: u32, overlay_w: u32, overlay_h: u32, padding: u32) -> (u32, u32) {
+    match position {
+        "bottom-left" => (padding, base_h - overlay_h - padding),
+        "bottom-right" => (base_w - overlay_w - padding, base_h - overlay_h - padding),
+        "top-left" => (padding, padding),
+        "top-right" => (base_w - overlay_w - padding, padding),
+        "center" => ((base_w - overlay_w) / 2, (base_h - overlay_h) / 2),
+        _ => (base_w - overlay_w - padding, base_h - overlay_h - padding), // default to bottom-right
     }
-    
-    let start = srt_to_ass_time(parts[0].trim())?;
-    let end = srt_to_ass_time(parts[1].trim())?;
-    
-    Some((start, end))
-}
-
-// Convert SRT time "00:00:00,000" to ASS time "0:00:00.00"
-fn srt_to_ass_time(srt_time: &str) -> Option<String> {
-    // Replace comma with dot for ASS format
-    let ass_time = srt_time.replace(",", ".");
-    Some(ass_time)
 }
 
 fn export_video_blocking(
@@ -882,148 +1141,401 @@ fn export_video_blocking(
     preset: String,
     overlay_positions: Option<OverlayPositions>,
     #[allow(non_snake_case)] subtitleSrtPath: Option<String>,
+    workers: Option<usize>,
+    target_vmaf: Option<f64>,
+    encoder: Option<encoder_backend::EncoderBackend>,
+    codec: Option<output_codec::OutputCodec>,
+    intermediate_quality: Option<bool>,
+    subtitle_style_preset: Option<subtitle_style::StylePreset>,
+    subtitle_karaoke: Option<bool>,
+    streaming_format: Option<streaming_format::StreamingFormat>,
+    segment_output: Option<bool>,
+    hdr_mode: Option<hdr::HdrMode>,
+    output_fps: Option<frame_rate::FrameRate>,
 ) -> Result<String, String> {
-    
+    let output_codec_choice = codec.unwrap_or(output_codec::OutputCodec::Libx264);
+    let use_software_codec = output_codec_choice != output_codec::OutputCodec::Libx264;
+    let output_container = std::path::Path::new(&outputPath)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mp4")
+        .to_lowercase();
+    // Default on: re-encoding an already-lossy intermediate at the final
+    // codec's (often slower) settings only pays off once, at the last pass.
+    let use_intermediate_quality = intermediate_quality.unwrap_or(true);
+    let streaming_format_choice = streaming_format.unwrap_or_default();
+    // Only meaningful once the chosen container is actually fragmented MP4.
+    let want_segments = segment_output.unwrap_or(false) && streaming_format_choice != streaming_format::StreamingFormat::Progressive;
+
     println!("=== EXPORT VIDEO BLOCKING STARTED ===");
     println!("Clips count: {}", sorted_clips.len());
     println!("Output path: {}", outputPath);
     println!("Subtitle SRT path: {:?}", subtitleSrtPath);
-    
-    // Separate clips by track
-    let mut track_0_clips: Vec<&ClipData> = sorted_clips.iter().filter(|c| c.track == 0).collect();
-    let track_1_clips: Vec<&ClipData> = sorted_clips.iter().filter(|c| c.track == 1).collect();
-    let track_2_clips: Vec<&ClipData> = sorted_clips.iter().filter(|c| c.track == 2).collect();
-    
-    let has_overlays = !track_1_clips.is_empty() || !track_2_clips.is_empty();
-    
-    // If only track 0 clips and no overlays, use simple concatenation
+
+    // When a target VMAF is given, each clip's CRF is probed independently (and
+    // cached per source file, so clips trimmed from the same recording don't
+    // re-probe) instead of using the fixed `crf` for every clip.
+    let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+    let ffprobe_path = find_ffmpeg_binary(Some(&app_handle), "ffprobe");
+    let crf_cache = vmaf::CrfCache::new(target_vmaf, crf.clone(), width, height, preset.clone());
+    let hdr_mode_choice = hdr_mode.unwrap_or_default();
+
+    // Probe (or, under a forced mode, skip probing) a clip's own source for
+    // HDR transfer/primaries/space, so its encode command knows whether to
+    // carry 10-bit PQ/HLG through untouched instead of crushing it to 8-bit SDR.
+    let resolve_hdr = |source_path: &str| -> hdr::HdrDecision {
+        let probe = if hdr_mode_choice == hdr::HdrMode::Auto {
+            hdr::probe_color_properties(&ffprobe_path, source_path)
+        } else {
+            hdr::ColorProps::default()
+        };
+        hdr::resolve(hdr_mode_choice, &probe)
+    };
+
+    // Pick the project's exact output frame rate as a rational, rather than a
+    // lossy float - defaults to Track 0's own first clip (probed via ffprobe's
+    // `r_frame_rate`) unless the caller pins one explicitly. Threaded through
+    // every encode pass below as `-r`/`-video_track_timescale`, so the concat
+    // demuxer sees uniform timing instead of clips shot at different frame
+    // rates (23.976 vs 30, etc.) drifting out of sync. The scene-chunked fast
+    // path passes neither flag and is only taken when the clip's source
+    // already runs at this rate.
+    let project_fps = output_fps.unwrap_or_else(|| {
+        sorted_clips
+            .iter()
+            .find(|c| c.track == 0)
+            .and_then(|c| frame_rate::probe_frame_rate(&ffprobe_path, &c.file_path))
+            .unwrap_or(frame_rate::FrameRate { num: 30, den: 1 })
+    });
+
+    // An explicit `fps=` filter is only needed when a clip's own source frame
+    // rate doesn't already match the project rate - inserted ahead of
+    // whatever else is in `vf_parts` so frames are duplicated/dropped on a
+    // clean cadence instead of relying on `-r` alone to implicitly retime.
+    let push_fps_filter = |vf_parts: &mut Vec<String>, source_path: &str| {
+        if frame_rate::probe_frame_rate(&ffprobe_path, source_path) != Some(project_fps) {
+            vf_parts.insert(0, format!("fps={}", project_fps.as_ffmpeg_arg()));
+        }
+    };
+
+    // Run once a final export file exists, to fold its codec/container choice
+    // into the success message and debug log, and - when requested - remux it
+    // into a discrete init segment plus numbered media segments. Returns the
+    // suffix to append to the "exported successfully to ..." message.
+    let finalize_streaming_format = |path: &str, reported_codec: output_codec::OutputCodec| -> String {
+        let mut note = format!(
+            " ({:?}, {}, {})",
+            reported_codec,
+            output_container,
+            streaming_format_choice.label()
+        );
+        if want_segments {
+            match streaming_format::write_segments(&ffmpeg_path, path, 4.0) {
+                Ok((init_path, segment_pattern)) => {
+                    let line = format!(
+                        "Segmented fragmented MP4: init={}, segments={}\n",
+                        init_path, segment_pattern
+                    );
+                    println!("{}", line.trim_end());
+                    use std::io::Write;
+                    if let Ok(mut f) = std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open("src-tauri/export_debug.log")
+                    {
+                        let _ = f.write_all(line.as_bytes());
+                    }
+                    note.push_str(&format!(", segments: {} + {}", init_path, segment_pattern));
+                }
+                Err(e) => {
+                    println!("WARNING: Failed to write fMP4 segments: {}", e);
+                }
+            }
+        }
+        note
+    };
+
+    // Log a freshly-probed CRF choice to stdout and the debug log so target-quality
+    // picks are visible without digging through FFmpeg's own output.
+    let log_crf_choice = |source_path: &str, chosen_crf: &str, vmaf_score: Option<f64>| {
+        if let Some(score) = vmaf_score {
+            let target = target_vmaf.unwrap_or(0.0);
+            let line = format!(
+                "Target-quality: {} -> CRF {} (measured VMAF {:.2}, target {:.2})\n",
+                source_path, chosen_crf, score, target
+            );
+            println!("{}", line.trim_end());
+            use std::io::Write;
+            if let Ok(mut f) = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open("src-tauri/export_debug.log")
+            {
+                let _ = f.write_all(line.as_bytes());
+            }
+        }
+    };
+
+    // Separate clips by track: track 0 is the main video, any track >= 1 is
+    // an overlay - there's no fixed overlay-track count, so this has to stay
+    // a predicate rather than a hardcoded list of track numbers.
+    let mut track_0_clips: Vec<&ClipData> = sorted_clips.iter().filter(|c| c.track == 0).collect();
+    let overlay_track_clips: Vec<&ClipData> = sorted_clips.iter().filter(|c| c.track >= 1).collect();
+
+    let has_overlays = !overlay_track_clips.is_empty();
+    
+    // If only track 0 clips and no overlays, use simple concatenation
     if !has_overlays {
         track_0_clips.sort_by(|a, b| a.start_time.partial_cmp(&b.start_time).unwrap());
     
         // If only one clip total and no overlays, use simple trimming
         if track_0_clips.len() == 1 {
             let clip = &track_0_clips[0];
-        
+
             // Validate input file exists
             if !std::path::Path::new(&clip.file_path).exists() {
                 return Err(format!("Input video file not found: {}", clip.file_path));
             }
-            
+
             let path = clip.file_path.replace("\\", "/");
-            
-            let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-            cmd.arg("-y");
-            
-            // Only add trim if needed
-            if clip.trim_start > 0.0 {
-                cmd.arg("-ss").arg(&format!("{:.3}", clip.trim_start));
-            }
-            
-            cmd.arg("-i").arg(&path);
-            
-            // Only add duration if trimmed
-            if clip.trim_start > 0.0 || clip.duration > 0.0 {
-                cmd.arg("-t").arg(&format!("{:.3}", clip.duration));
-            }
-            
-            // Use configured preset and quality
-            cmd.arg("-c:v").arg("libx264");
-            cmd.arg("-preset").arg(&preset);
-            cmd.arg("-crf").arg(&crf);
-            cmd.arg("-c:a").arg("aac"); // Re-encode audio for compatibility
-            cmd.arg("-b:a").arg("192k"); // Audio bitrate
-            cmd.arg("-pix_fmt").arg("yuv420p"); // Compatible pixel format
-            cmd.arg("-movflags").arg("faststart"); // Web-friendly
-            
-            // Build video filter (scale + filters + optional subtitles)
-            let mut vf_parts = Vec::new();
-            
-            // Add scale filter first
-            if width > 0 && height > 0 {
-                vf_parts.push(format!("scale={}:{}", width, height));
-            }
-            
-            // Add eq filter for brightness/contrast/saturation if present
-            if let Some(eq_filter) = build_eq_filter(&clip.filters) {
-                println!("ðŸŽ¨ Applying filters: {}", eq_filter);
-                vf_parts.push(eq_filter);
-            }
-            
-            // Add subtitles if provided - use relative path workaround to avoid Windows colon issues
-            if let Some(ref srt_path) = subtitleSrtPath {
-                println!("ðŸŽ¬ Burning subtitles from: {}", srt_path);
-                
-                if !std::path::Path::new(srt_path).exists() {
-                    return Err(format!("Subtitle file not found: {}", srt_path));
-                }
-                
-                // Read SRT content
-                let srt_content = std::fs::read_to_string(srt_path)
-                    .map_err(|e| format!("Failed to read SRT: {}", e))?;
-                
-                println!("SRT content ({} bytes)", srt_content.len());
-                
-                // WORKAROUND: Copy SRT to current directory (no drive letter path issues)
-                let simple_srt = std::path::PathBuf::from("./temp_subtitles.srt");
-                
-                std::fs::copy(srt_path, &simple_srt)
-                    .map_err(|e| format!("Failed to copy SRT: {}", e))?;
-                
-                println!("Copied SRT to: {:?}", simple_srt);
-                
-                // Use relative path - no drive letter issues!
-                let subtitle_filter = "subtitles=./temp_subtitles.srt".to_string();
-                
-                println!("Subtitle filter: {}", subtitle_filter);
-                
-                vf_parts.push(subtitle_filter);
-            }
-            
-            if !vf_parts.is_empty() {
-                let vf_filter = vf_parts.join(",");
-                println!("=== APPLYING VIDEO FILTER ===");
-                println!("Video filter: {}", vf_filter);
-                cmd.arg("-vf").arg(vf_filter);
-            } else {
-                println!("WARNING: No video filters applied (no scale, no subtitles)");
+            let (crf, vmaf_score) = crf_cache.crf_for(&ffmpeg_path, &clip.file_path)?;
+            log_crf_choice(&clip.file_path, &crf, vmaf_score);
+
+            let speed_segments = clip.speed_segments.clone().unwrap_or_default();
+            if !speed_segments.is_empty() {
+                speed_ramp::validate_segments(&speed_segments, clip.duration)?;
             }
-            
-            // Add flags - use error level if subtitles are present for debugging
-            cmd.arg("-hide_banner");
-            if subtitleSrtPath.is_some() {
-                cmd.arg("-loglevel").arg("error"); // Show errors when subtitles are involved
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::piped());
+            // With only one clip on the timeline, the clip's own trimmed
+            // timeline *is* the export's timeline, so subtitle cues can be
+            // remapped directly through this clip's speed segments.
+            let subtitle_time_remap: Option<Box<dyn Fn(f64) -> f64>> = if speed_segments.is_empty() {
+                None
             } else {
-                cmd.arg("-loglevel").arg("quiet");
-                cmd.arg("-nostats");
-                cmd.stdout(Stdio::null());
-                cmd.stderr(Stdio::null());
+                let segments = speed_segments.clone();
+                let duration = clip.duration;
+                Some(Box::new(move |t: f64| speed_ramp::remap_time(t, &segments, duration)))
+            };
+
+            // For a plain trim with no subtitles/color filters/grain/speed segments,
+            // from a source already at the project frame rate, try the parallel
+            // scene-chunked encode first - it keeps every core busy on long exports
+            // instead of one libx264 pass idling all but one. Anything it can't
+            // handle (or scene detection failing outright) falls through to the
+            // original single-pass path below.
+            if !use_software_codec
+                && streaming_format_choice == streaming_format::StreamingFormat::Progressive
+                && subtitleSrtPath.is_none()
+                && build_eq_filter(&clip.filters).is_none()
+                && speed_segments.is_empty()
+                && clip_grain_strength(&clip.filters) == 0
+                && frame_rate::probe_frame_rate(&ffprobe_path, &clip.file_path) == Some(project_fps)
+            {
+                let chunk_source = if clip.trim_start > 0.0 || clip.duration > 0.0 {
+                    let trimmed = std::env::temp_dir().join(format!(
+                        "vibeclips_chunk_source_{}.mp4",
+                        std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap()
+                            .as_millis()
+                    ));
+                    let status = create_hidden_command(Some(&app_handle), "ffmpeg")
+                        .arg("-y")
+                        .arg("-ss").arg(&format!("{:.3}", clip.trim_start))
+                        .arg("-i").arg(&path)
+                        .arg("-t").arg(&format!("{:.3}", clip.duration))
+                        .arg("-c").arg("copy")
+                        .arg(&trimmed)
+                        .arg("-hide_banner")
+                        .arg("-loglevel").arg("quiet")
+                        .status();
+                    match status {
+                        Ok(s) if s.success() => Some(trimmed),
+                        _ => None,
+                    }
+                } else {
+                    Some(std::path::PathBuf::from(&path))
+                };
+
+                if let Some(source) = chunk_source {
+                    match chunked_export::export_chunked(
+                        source.to_str().unwrap(),
+                        &outputPath,
+                        width,
+                        height,
+                        &crf,
+                        &preset,
+                        workers,
+                        &app_handle,
+                        "export-progress",
+                        &ffmpeg_path,
+                        &ffprobe_path,
+                    ) {
+                        Ok(result_path) => {
+                            if source != std::path::PathBuf::from(&path) {
+                                let _ = std::fs::remove_file(&source);
+                            }
+                            return Ok(format!("Video exported successfully to {}", result_path));
+                        }
+                        Err(e) => {
+                            println!("Chunked export unavailable, falling back to single pass: {}", e);
+                            if source != std::path::PathBuf::from(&path) {
+                                let _ = std::fs::remove_file(&source);
+                            }
+                        }
+                    }
+                }
             }
-            
-            cmd.arg(&outputPath);
-            
-            let output = if subtitleSrtPath.is_some() {
-                cmd.output()
-                    .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?
-            } else {
-                // For non-subtitle exports, use status() for better performance
-                let status = cmd.status()
-                    .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?;
-                return if status.success() {
-                    if std::path::Path::new(&outputPath).exists() {
-                        let _ = std::fs::write("src-tauri/export_debug.log", "=== EXPORT SUCCESS ===\n");
-                        Ok(format!("Video exported successfully to {}", outputPath))
+
+            // Synthetic film-grain (per-clip, alongside the eq filter below): SVT-AV1
+            // takes its own native grain level directly, so it's folded into the
+            // codec args here rather than the `-vf` chain used by every other codec.
+            let grain_strength = clip_grain_strength(&clip.filters);
+            let is_av1_output = use_software_codec && output_codec_choice == output_codec::OutputCodec::Av1;
+
+            let export_duration = speed_ramp::remapped_duration(&speed_segments, clip.duration);
+
+            // Builds and runs the single-clip FFmpeg command for a given encoder
+            // plan (hardware or software), so it can be re-run verbatim against a
+            // libx264 fallback plan if a hardware backend's encode fails below.
+            let run_single_clip = |plan_input_args: &[String], plan_filter_suffix: Option<&'static str>, plan_codec_args: &[String]| -> Result<std::process::Output, String> {
+                let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
+                cmd.arg("-y");
+                for arg in plan_input_args {
+                    cmd.arg(arg);
+                }
+
+                // Only add trim if needed
+                if clip.trim_start > 0.0 {
+                    cmd.arg("-ss").arg(&format!("{:.3}", clip.trim_start));
+                }
+
+                cmd.arg("-i").arg(&path);
+
+                // Only add duration if trimmed
+                if clip.trim_start > 0.0 || clip.duration > 0.0 {
+                    cmd.arg("-t").arg(&format!("{:.3}", clip.duration));
+                }
+
+                // Use configured preset and quality, resolved against whatever hardware
+                // encoder backend (or software output codec) was requested.
+                for arg in plan_codec_args {
+                    cmd.arg(arg);
+                }
+                if output_container == "webm" {
+                    cmd.arg("-c:a").arg("libopus"); // WebM only muxes Vorbis/Opus audio
+                    cmd.arg("-b:a").arg("192k");
+                } else {
+                    cmd.arg("-c:a").arg("aac"); // Re-encode audio for compatibility
+                    cmd.arg("-b:a").arg("192k"); // Audio bitrate
+                    cmd.arg("-movflags").arg(streaming_format_choice.movflags());
+                }
+                let hdr_decision = resolve_hdr(&clip.file_path);
+                cmd.arg("-pix_fmt").arg(hdr_decision.pix_fmt);
+                for arg in &hdr_decision.color_args {
+                    cmd.arg(arg);
+                }
+                cmd.arg("-r").arg(project_fps.as_ffmpeg_arg());
+                cmd.arg("-video_track_timescale").arg(project_fps.num.to_string());
+
+                // Build video filter (scale + filters + optional subtitles)
+                let mut vf_parts = Vec::new();
+
+                // Add scale filter first
+                if width > 0 && height > 0 {
+                    vf_parts.push(format!("scale={}:{}", width, height));
+                }
+                push_fps_filter(&mut vf_parts, &clip.file_path);
+
+                // Add eq filter for brightness/contrast/saturation if present
+                if let Some(eq_filter) = build_eq_filter(&clip.filters) {
+                    println!("ðŸŽ¨ Applying filters: {}", eq_filter);
+                    vf_parts.push(eq_filter);
+                }
+
+                // AV1 output already got grain via its native `-svtav1-params` above;
+                // every other codec falls back to the `noise` filter.
+                if !is_av1_output {
+                    if let Some(noise) = film_grain::noise_filter_fallback(grain_strength) {
+                        vf_parts.push(noise);
+                    }
+                }
+
+                // Add subtitles if provided, rendered through the styling engine
+                if let Some(ref srt_path) = subtitleSrtPath {
+                    let subtitle_filter = prepare_subtitle_filter(
+                        srt_path,
+                        subtitle_style_preset.unwrap_or_default(),
+                        subtitle_karaoke.unwrap_or(false),
+                        subtitle_time_remap.as_deref(),
+                    )?;
+                    vf_parts.push(subtitle_filter);
+                }
+
+                // Hardware backends that need frames uploaded into device memory
+                // (currently VAAPI) append their upload filter last, after scaling/eq.
+                if let Some(suffix) = plan_filter_suffix {
+                    vf_parts.push(suffix.to_string());
+                }
+
+                if speed_segments.is_empty() {
+                    if !vf_parts.is_empty() {
+                        let vf_filter = vf_parts.join(",");
+                        println!("=== APPLYING VIDEO FILTER ===");
+                        println!("Video filter: {}", vf_filter);
+                        cmd.arg("-vf").arg(vf_filter);
                     } else {
-                        let _ = std::fs::write("src-tauri/export_debug.log", "=== EXPORT FAILED: File not created ===\n");
-                        Err("FFmpeg completed but output file was not created".to_string())
+                        println!("WARNING: No video filters applied (no scale, no subtitles)");
                     }
                 } else {
-                    let _ = std::fs::write("src-tauri/export_debug.log", format!("=== EXPORT FAILED: exit code {:?} ===\n", status.code()).as_str());
-                    Err(format!("FFmpeg failed with exit code: {:?}", status.code()))
-                };
+                    // A speed ramp retimes the stream via trim/concat, so it needs
+                    // `-filter_complex` (and explicit `-map`s) instead of `-vf`; any
+                    // other filters above are folded in as one more stage on top of
+                    // the ramped `[vramp]` output.
+                    let mut filter_complex = speed_ramp::build_filter_complex(
+                        "0:v", "0:a", &speed_segments, clip.duration, "vramp", "aout",
+                    );
+                    let vout_label = if vf_parts.is_empty() {
+                        "vramp"
+                    } else {
+                        filter_complex.push_str(&format!(";[vramp]{}[vout]", vf_parts.join(",")));
+                        "vout"
+                    };
+                    println!("=== APPLYING SPEED RAMP FILTER COMPLEX ===");
+                    println!("Filter complex: {}", filter_complex);
+                    cmd.arg("-filter_complex").arg(filter_complex);
+                    cmd.arg("-map").arg(format!("[{}]", vout_label));
+                    cmd.arg("-map").arg("[aout]");
+                }
+
+                // Add flags
+                cmd.arg("-hide_banner");
+                cmd.arg(&outputPath);
+
+                export_progress::run_with_progress(cmd, &app_handle, export_duration, "export-progress")
             };
-            
+
+            // A non-default output codec (VP9/AV1/x265) bypasses the hardware
+            // backend entirely - those are software-only encoders here, selected
+            // for container/format reasons rather than encode speed. Otherwise
+            // resolve the requested hardware backend against what's actually
+            // available, and if the encode itself fails partway through (a
+            // flaky NVENC/VAAPI driver), retry the exact same clip in software
+            // libx264 rather than hard-failing the whole export.
+            let (output, used_backend) = if use_software_codec {
+                let mut plan_codec_args = output_codec_choice.final_args(&crf, &preset, &output_container);
+                if is_av1_output {
+                    plan_codec_args.extend(film_grain::svtav1_grain_args(grain_strength));
+                }
+                (run_single_clip(&[], None, &plan_codec_args)?, None)
+            } else {
+                let requested = encoder.unwrap_or(encoder_backend::EncoderBackend::Auto);
+                let resolved_backend = encoder_backend::plan_for(requested, &crf, &preset, &ffmpeg_path).backend;
+                let output = encoder_backend::run_with_hardware_fallback(requested, &crf, &preset, &ffmpeg_path, |plan| {
+                    run_single_clip(&plan.input_args, plan.filter_suffix, &plan.codec_args)
+                })?;
+                (output, Some(resolved_backend))
+            };
+
             if !output.status.success() {
                 let error_msg = String::from_utf8_lossy(&output.stderr);
                 let stdout_msg = String::from_utf8_lossy(&output.stdout);
@@ -1056,7 +1568,14 @@ fn export_video_blocking(
                     "=== EXPORT SUCCESS ===\n"
                 };
                 let _ = std::fs::write("src-tauri/export_debug.log", log_msg);
-                return Ok(format!("Video exported successfully to {}", outputPath));
+                let backend_note = match used_backend {
+                    Some(backend) if backend != encoder_backend::EncoderBackend::Libx264 => {
+                        format!(" (via {:?})", backend)
+                    }
+                    _ => String::new(),
+                };
+                let streaming_note = finalize_streaming_format(&outputPath, output_codec_choice);
+                return Ok(format!("Video exported successfully to {}{}{}", outputPath, backend_note, streaming_note));
             } else if status.success() {
                 let _ = std::fs::write("src-tauri/export_debug.log", "=== EXPORT FAILED: File not created despite success code ===\n");
                 return Err("FFmpeg completed but output file was not created".to_string());
@@ -1066,73 +1585,185 @@ fn export_video_blocking(
             }
         }
         
-        // Multiple clips on track 0: create temporary trimmed files then concat them
-        use std::env;
-        let temp_dir = env::temp_dir();
-        
-        let mut temp_files = Vec::new();
-        
-        // Step 1: Trim each clip to a temp file
-        for (i, clip) in track_0_clips.iter().enumerate() {
-        let temp_file = temp_dir.join(format!("clip_{}.mp4", i));
-        temp_files.push(temp_file.clone());
-        
-        let path = clip.file_path.replace("\\", "/");
-        
-        let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-        cmd.arg("-y");
-        
-        if clip.trim_start > 0.0 {
-            cmd.arg("-ss").arg(&format!("{:.3}", clip.trim_start));
-        }
-        
-        cmd.arg("-i").arg(&path);
-        
-        if clip.duration > 0.0 {
-            cmd.arg("-t").arg(&format!("{:.3}", clip.duration));
-        }
-        
-        cmd.arg("-c:v").arg("libx264");
-        cmd.arg("-preset").arg(&preset);
-        cmd.arg("-crf").arg(&crf);
-        cmd.arg("-c:a").arg("aac");
-        cmd.arg("-b:a").arg("192k");
-        cmd.arg("-pix_fmt").arg("yuv420p");
-        
-        // Build video filter (scale + filters)
-        let mut vf_parts = Vec::new();
-        if width > 0 && height > 0 {
-            vf_parts.push(format!("scale={}:{}", width, height));
-        }
-        
-        // Add eq filter for brightness/contrast/saturation if present
-        if let Some(eq_filter) = build_eq_filter(&clip.filters) {
-            println!("ðŸŽ¨ Applying filters to clip {}: {}", i, eq_filter);
-            vf_parts.push(eq_filter);
-        }
-        
-        if !vf_parts.is_empty() {
-            cmd.arg("-vf").arg(vf_parts.join(","));
-        }
-        
-        // Add flags to suppress ALL output
-        cmd.arg("-hide_banner");
-        cmd.arg("-loglevel").arg("quiet");
-        cmd.arg("-nostats");
-        
-        cmd.arg(temp_file.to_str().unwrap());
-        
-        // Suppress all output
-        cmd.stdout(Stdio::null());
-        cmd.stderr(Stdio::null());
-        
-        let status = cmd.status()
-            .map_err(|e| format!("Failed to execute FFmpeg for clip {}: {}", i, e))?;
-        
-        if !status.success() {
-            return Err(format!("Failed to trim clip {}: exit code {:?}", i, status.code()));
+        // Multiple clips on track 0: create temporary trimmed files then concat them.
+        // Each export gets its own temp subdirectory so concurrent exports never
+        // collide on `clip_0.mp4`.
+        let temp_dir = std::env::temp_dir().join(format!(
+            "vibeclips_export_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis()
+        ));
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(|e| format!("Failed to create export temp dir: {}", e))?;
+
+        let temp_files: Vec<std::path::PathBuf> = (0..track_0_clips.len())
+            .map(|i| temp_dir.join(format!("clip_{}.mp4", i)))
+            .collect();
+
+        // Step 1: Trim each clip to a temp file, in parallel across a worker pool
+        // (modeled on chunked_export's work-queue pool) instead of serially - long
+        // multi-clip timelines otherwise leave most CPU cores idle.
+        //
+        // Builds the per-clip trim jobs. `force_software` is set on a retry after
+        // a hardware backend failed partway through the pool below, so every job
+        // in that retry is rebuilt against plain libx264 instead of the requested
+        // backend.
+        let build_trim_jobs = |force_software: bool| -> Result<Vec<clip_trim_pool::ClipTrimJob>, String> {
+            track_0_clips
+                .iter()
+                .enumerate()
+                .map(|(i, clip)| -> Result<clip_trim_pool::ClipTrimJob, String> {
+                    // This temp file is an intermediate that Step 3 re-encodes into the
+                    // final codec/CRF; encoding it at a fast, high-bitrate setting avoids
+                    // paying the final codec's (often much slower) encode cost twice and
+                    // avoids stacking two lossy low-bitrate passes on top of each other.
+                    // Only probe for a per-clip VMAF-targeted CRF when that CRF is
+                    // actually going to be used (intermediate mode always uses its own
+                    // fixed fast settings here).
+                    let (input_args, filter_suffix, video_args) = if use_intermediate_quality {
+                        (Vec::new(), None, output_codec::OutputCodec::intermediate_args())
+                    } else {
+                        let (clip_crf, vmaf_score) = crf_cache.crf_for(&ffmpeg_path, &clip.file_path)?;
+                        log_crf_choice(&clip.file_path, &clip_crf, vmaf_score);
+                        if use_software_codec || force_software {
+                            (Vec::new(), None, vec![
+                                "-c:v".to_string(), "libx264".to_string(),
+                                "-preset".to_string(), preset.clone(),
+                                "-crf".to_string(), clip_crf,
+                            ])
+                        } else {
+                            // A non-default output codec bypasses the hardware backend
+                            // entirely above (`use_software_codec`); otherwise this
+                            // intermediate pass also benefits from the requested
+                            // backend, with the whole pool retried in software below
+                            // if a hardware job in it fails.
+                            let requested = encoder.unwrap_or(encoder_backend::EncoderBackend::Auto);
+                            let plan = encoder_backend::plan_for(requested, &clip_crf, &preset, &ffmpeg_path);
+                            (plan.input_args, plan.filter_suffix, plan.codec_args)
+                        }
+                    };
+
+                    let mut vf_parts = Vec::new();
+                    if width > 0 && height > 0 {
+                        vf_parts.push(format!("scale={}:{}", width, height));
+                    }
+                    if let Some(eq_filter) = build_eq_filter(&clip.filters) {
+                        println!("ðŸŽ¨ Applying filters to clip {}: {}", i, eq_filter);
+                        vf_parts.push(eq_filter);
+                    }
+                    // This intermediate pass encodes with libx264 or the requested
+                    // hardware backend, never SVT-AV1, so grain synthesis here always
+                    // uses the noise-filter fallback; Step 3 below adds no grain of
+                    // its own, whatever the final codec.
+                    if let Some(noise) = film_grain::noise_filter_fallback(clip_grain_strength(&clip.filters)) {
+                        vf_parts.push(noise);
+                    }
+                    if let Some(suffix) = filter_suffix {
+                        vf_parts.push(suffix.to_string());
+                    }
+
+                    let speed_segments = clip.speed_segments.clone().unwrap_or_default();
+                    if !speed_segments.is_empty() {
+                        speed_ramp::validate_segments(&speed_segments, clip.duration)?;
+                    }
+
+                    let hdr_decision = resolve_hdr(&clip.file_path);
+                    let needs_fps_filter = frame_rate::probe_frame_rate(&ffprobe_path, &clip.file_path) != Some(project_fps);
+                    Ok(clip_trim_pool::ClipTrimJob {
+                        index: i,
+                        input_path: clip.file_path.replace("\\", "/"),
+                        trim_start: clip.trim_start,
+                        duration: clip.duration,
+                        input_args,
+                        video_args,
+                        vf_filter: if vf_parts.is_empty() { None } else { Some(vf_parts.join(",")) },
+                        speed_segments,
+                        output_path: temp_files[i].clone(),
+                        pix_fmt: hdr_decision.pix_fmt,
+                        color_args: hdr_decision.color_args,
+                        fps: project_fps,
+                        needs_fps_filter,
+                        ffmpeg_path: ffmpeg_path.clone(),
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()
+        };
+
+        let jobs = build_trim_jobs(false)?;
+        // Whether this batch actually asked a hardware backend to do anything -
+        // only then is a pool failure worth retrying against libx264 below.
+        let used_hardware = !use_intermediate_quality
+            && !use_software_codec
+            && encoder_backend::plan_for(
+                encoder.unwrap_or(encoder_backend::EncoderBackend::Auto),
+                &crf,
+                &preset,
+                &ffmpeg_path,
+            ).backend != encoder_backend::EncoderBackend::Libx264;
+
+        // Each track-0 clip's own `speed_segments` compress/expand its slice
+        // of the concatenated timeline; walk the clips in order to build a
+        // global remap from "time on the original (pre-ramp) timeline" (what
+        // the SRT is authored against) to "time on the post-ramp, post-concat
+        // timeline" (what's actually burned in), so Step 3's subtitles below
+        // stay in sync with any ramped clips.
+        let mut subtitle_clip_spans = Vec::with_capacity(track_0_clips.len());
+        let mut orig_cursor = 0.0;
+        let mut post_cursor = 0.0;
+        for clip in &track_0_clips {
+            let segments = clip.speed_segments.clone().unwrap_or_default();
+            let post_duration = speed_ramp::remapped_duration(&segments, clip.duration);
+            subtitle_clip_spans.push((orig_cursor, clip.duration, segments, post_cursor));
+            orig_cursor += clip.duration;
+            post_cursor += post_duration;
         }
-    }
+        let any_ramped_clip = subtitle_clip_spans.iter().any(|(_, _, segs, _)| !segs.is_empty());
+        let subtitle_time_remap: Option<Box<dyn Fn(f64) -> f64>> = if any_ramped_clip {
+            Some(Box::new(move |t: f64| {
+                let last = subtitle_clip_spans.len().saturating_sub(1);
+                for (i, (orig_start, duration, segments, post_start)) in subtitle_clip_spans.iter().enumerate() {
+                    if t < orig_start + duration || i == last {
+                        let local_t = (t - orig_start).clamp(0.0, *duration);
+                        return post_start + speed_ramp::remap_time(local_t, segments, *duration);
+                    }
+                }
+                t
+            }))
+        } else {
+            None
+        };
+
+        let progress_handle = app_handle.clone();
+        let trim_result = clip_trim_pool::run_trim_pool(jobs, workers, move |completed, total| {
+            let _ = progress_handle.emit(
+                "export-chunk-progress",
+                clip_trim_pool::TrimPoolProgress { completed, total },
+            );
+        });
+
+        // Same rationale as `run_with_hardware_fallback`: a flaky NVENC/VAAPI
+        // driver shouldn't hard-fail the whole export, so retry the entire
+        // trim pass in software libx264 once if a hardware job in it failed.
+        let trim_result = if trim_result.is_err() && used_hardware {
+            println!("Hardware encode failed during clip trim pass, retrying with software libx264");
+            let fallback_jobs = build_trim_jobs(true)?;
+            let progress_handle = app_handle.clone();
+            clip_trim_pool::run_trim_pool(fallback_jobs, workers, move |completed, total| {
+                let _ = progress_handle.emit(
+                    "export-chunk-progress",
+                    clip_trim_pool::TrimPoolProgress { completed, total },
+                );
+            })
+        } else {
+            trim_result
+        };
+
+        trim_result.map_err(|e| {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            format!("Failed to trim clips: {}", e)
+        })?;
     
     // Step 2: Create concat file list
     let concat_file = temp_dir.join("concat_list.txt");
@@ -1145,105 +1776,125 @@ fn export_video_blocking(
         .map_err(|e| format!("Failed to write concat file: {}", e))?;
     
     // Step 3: Concat all temp files (with optional subtitles)
-    let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-    cmd.arg("-y");
-    cmd.arg("-f").arg("concat");
-    cmd.arg("-safe").arg("0");
-    cmd.arg("-i").arg(concat_file.to_str().unwrap());
-    
+    //
     // Build video filter (re-encode + optional subtitles)
     // Note: Individual clip filters are already applied when processing each clip
-    let mut vf_parts = Vec::new();
+    let mut vf_parts_base = Vec::new();
     if width > 0 && height > 0 {
-        vf_parts.push(format!("scale={}:{}", width, height));
+        vf_parts_base.push(format!("scale={}:{}", width, height));
     }
-    
-    // Add subtitles if provided - use relative path workaround to avoid Windows colon issues
+
+    // Add subtitles if provided, rendered through the styling engine
     if let Some(ref srt_path) = subtitleSrtPath {
-        println!("ðŸŽ¬ Burning subtitles from: {}", srt_path);
-        
-        if !std::path::Path::new(srt_path).exists() {
-            return Err(format!("Subtitle file not found: {}", srt_path));
-        }
-        
-        // Read SRT content
-        let srt_content = std::fs::read_to_string(srt_path)
-            .map_err(|e| format!("Failed to read SRT: {}", e))?;
-        
-        println!("SRT content ({} bytes)", srt_content.len());
-        
-        // WORKAROUND: Copy SRT to current directory (no drive letter path issues)
-        let simple_srt = std::path::PathBuf::from("./temp_subtitles.srt");
-        
-        std::fs::copy(srt_path, &simple_srt)
-            .map_err(|e| format!("Failed to copy SRT: {}", e))?;
-        
-        println!("Copied SRT to: {:?}", simple_srt);
-        
-        // Use relative path - no drive letter issues!
-        let subtitle_filter = "subtitles=./temp_subtitles.srt".to_string();
-        
-        println!("Subtitle filter: {}", subtitle_filter);
-        
-        vf_parts.push(subtitle_filter);
-    }
-    
-    if !vf_parts.is_empty() {
-        let vf_filter = vf_parts.join(",");
-        println!("Video filter: {}", vf_filter);
-        cmd.arg("-vf").arg(vf_filter);
-        // Re-encode video when applying filters
-        cmd.arg("-c:v").arg("libx264");
-        cmd.arg("-preset").arg(&preset);
-        cmd.arg("-crf").arg(&crf);
-        cmd.arg("-pix_fmt").arg("yuv420p");
-    } else {
-        cmd.arg("-c").arg("copy");
+        let subtitle_filter = prepare_subtitle_filter(
+            srt_path,
+            subtitle_style_preset.unwrap_or_default(),
+            subtitle_karaoke.unwrap_or(false),
+            subtitle_time_remap.as_deref(),
+        )?;
+        vf_parts_base.push(subtitle_filter);
     }
-    cmd.arg("-c:a").arg("aac");
-    cmd.arg("-b:a").arg("192k");
-    
-    // Add flags - use error level if subtitles are present for debugging
-    cmd.arg("-hide_banner");
-    if subtitleSrtPath.is_some() {
-        cmd.arg("-loglevel").arg("error");
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
-    } else {
-        cmd.arg("-loglevel").arg("quiet");
-        cmd.arg("-nostats");
-        cmd.stdout(Stdio::null());
-        cmd.stderr(Stdio::null());
-    }
-    
-    cmd.arg(&outputPath);
-    
-    let output = if subtitleSrtPath.is_some() {
-        cmd.output()
-            .map_err(|e| format!("Failed to execute FFmpeg concat: {}", e))?
-    } else {
-        // For non-subtitle exports, use status()
-        let status = cmd.status()
-            .map_err(|e| format!("Failed to execute FFmpeg concat: {}", e))?;
-        if status.success() {
-                let _ = std::fs::write("src-tauri/export_debug.log", "=== EXPORT SUCCESS ===\n");
-            // Cleanup temp files
-            for temp_file in &temp_files {
-                let _ = std::fs::remove_file(temp_file);
+
+    // Step 1's clips were only encoded at the final codec/CRF when
+    // intermediate_quality was disabled and the final codec is libx264; in
+    // that one case this concat pass can stream-copy the video. Otherwise
+    // (the default) Step 1 used fast intermediate settings or a different
+    // codec family entirely, so this final pass has to re-encode for real.
+    let step1_already_final = !use_intermediate_quality && !use_software_codec;
+    let needs_reencode = !(vf_parts_base.is_empty() && step1_already_final);
+
+    // Runs the Step 3 concat-and-encode pass for a given encoder plan (or no
+    // plan at all for the stream-copy case), so a hardware backend's plan can
+    // be re-run verbatim against a libx264 fallback if its encode fails below.
+    let run_concat_pass = |plan: Option<(&[String], Option<&'static str>, &[String])>| -> Result<std::process::Output, String> {
+        let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
+        cmd.arg("-y");
+        if let Some((input_args, _, _)) = plan {
+            for arg in input_args {
+                cmd.arg(arg);
             }
-            let _ = std::fs::remove_file(&concat_file);
-            return Ok(format!("Video exported successfully to {}", outputPath))
-        } else {
-            // Cleanup temp files on failure
-            for temp_file in &temp_files {
-                let _ = std::fs::remove_file(temp_file);
+        }
+        cmd.arg("-f").arg("concat");
+        cmd.arg("-safe").arg("0");
+        cmd.arg("-i").arg(concat_file.to_str().unwrap());
+
+        let mut vf_parts = vf_parts_base.clone();
+        if let Some((_, filter_suffix, _)) = plan {
+            // Hardware backends that need frames uploaded into device memory
+            // (currently VAAPI) append their upload filter last.
+            if let Some(suffix) = filter_suffix {
+                vf_parts.push(suffix.to_string());
             }
-            let _ = std::fs::remove_file(&concat_file);
-                let _ = std::fs::write("src-tauri/export_debug.log", format!("=== EXPORT FAILED: exit code {:?} ===\n", status.code()).as_str());
-            return Err(format!("Concatenation failed with exit code: {:?}", status.code()))
         }
+        if !vf_parts.is_empty() {
+            let vf_filter = vf_parts.join(",");
+            println!("Video filter: {}", vf_filter);
+            cmd.arg("-vf").arg(vf_filter);
+        }
+
+        // Every Step 1 clip was already forced to `project_fps`, so the concat
+        // demuxer's inputs already share one cadence; the timescale still needs
+        // pinning explicitly here so the muxed output stays on that same uniform
+        // timing rather than whatever the container's default happens to be.
+        cmd.arg("-video_track_timescale").arg(project_fps.num.to_string());
+        match plan {
+            None => {
+                cmd.arg("-c:v").arg("copy");
+            }
+            Some((_, _, codec_args)) => {
+                for arg in codec_args {
+                    cmd.arg(arg);
+                }
+                // All track-0 clips share one output file here, so the HDR-ness of
+                // the first clip (the common case: one recording cut into several
+                // timeline pieces) decides the whole pass's pix_fmt/color tags.
+                let hdr_decision = resolve_hdr(&track_0_clips[0].file_path);
+                cmd.arg("-pix_fmt").arg(hdr_decision.pix_fmt);
+                for arg in &hdr_decision.color_args {
+                    cmd.arg(arg);
+                }
+                cmd.arg("-r").arg(project_fps.as_ffmpeg_arg());
+            }
+        }
+
+        if output_container == "webm" {
+            cmd.arg("-c:a").arg("libopus");
+        } else {
+            cmd.arg("-c:a").arg("aac");
+            cmd.arg("-movflags").arg(streaming_format_choice.movflags());
+        }
+        cmd.arg("-b:a").arg("192k");
+
+        // Add flags - use error level if subtitles are present for debugging
+        cmd.arg("-hide_banner");
+        if subtitleSrtPath.is_some() {
+            cmd.arg("-loglevel").arg("error");
+        } else {
+            cmd.arg("-loglevel").arg("quiet");
+            cmd.arg("-nostats");
+        }
+
+        cmd.arg(&outputPath);
+
+        cmd.output().map_err(|e| format!("Failed to execute FFmpeg concat: {}", e))
     };
-    
+
+    // A non-default output codec bypasses the hardware backend entirely, same
+    // as the single-clip path above; when a hardware backend is in play,
+    // retry this same concat pass in software libx264 if its encode fails
+    // rather than hard-failing the whole export.
+    let output = if !needs_reencode {
+        run_concat_pass(None)?
+    } else if use_software_codec {
+        let codec_args = output_codec_choice.final_args(&crf, &preset, &output_container);
+        run_concat_pass(Some((&[], None, &codec_args)))?
+    } else {
+        let requested = encoder.unwrap_or(encoder_backend::EncoderBackend::Auto);
+        encoder_backend::run_with_hardware_fallback(requested, &crf, &preset, &ffmpeg_path, |plan| {
+            run_concat_pass(Some((&plan.input_args, plan.filter_suffix, &plan.codec_args)))
+        })?
+    };
+
     if !output.status.success() {
         let error_msg = String::from_utf8_lossy(&output.stderr);
         let stdout_msg = String::from_utf8_lossy(&output.stdout);
@@ -1270,7 +1921,8 @@ fn export_video_blocking(
     
     if status.success() {
                 let _ = std::fs::write("src-tauri/export_debug.log", "=== EXPORT SUCCESS ===\n");
-            Ok(format!("Video exported successfully to {}", outputPath))
+            let streaming_note = finalize_streaming_format(&outputPath, output_codec_choice);
+            Ok(format!("Video exported successfully to {}{}", outputPath, streaming_note))
         } else {
                 let _ = std::fs::write("src-tauri/export_debug.log", format!("=== EXPORT FAILED: exit code {:?} ===\n", status.code()).as_str());
             Err(format!("Concatenation failed with exit code: {:?}", status.code()))
@@ -1322,24 +1974,57 @@ fn export_video_blocking(
             cmd.arg("-crf").arg(&crf);
             cmd.arg("-c:a").arg("aac");
             cmd.arg("-b:a").arg("192k");
-            cmd.arg("-pix_fmt").arg("yuv420p");
-            
+            let hdr_decision = resolve_hdr(&clip.file_path);
+            cmd.arg("-pix_fmt").arg(hdr_decision.pix_fmt);
+            for arg in &hdr_decision.color_args {
+                cmd.arg(arg);
+            }
+            cmd.arg("-r").arg(project_fps.as_ffmpeg_arg());
+            cmd.arg("-video_track_timescale").arg(project_fps.num.to_string());
+
             // Build video filter (scale + filters)
             let mut vf_parts = Vec::new();
             if width > 0 && height > 0 {
                 vf_parts.push(format!("scale={}:{}", width, height));
             }
+            push_fps_filter(&mut vf_parts, &clip.file_path);
             
             // Add eq filter for brightness/contrast/saturation if present
             if let Some(eq_filter) = build_eq_filter(&clip.filters) {
                 println!("ðŸŽ¨ Applying filters to Track 0 clip {}: {}", i, eq_filter);
                 vf_parts.push(eq_filter);
             }
-            
-            if !vf_parts.is_empty() {
-                cmd.arg("-vf").arg(vf_parts.join(","));
+
+            // This branch always encodes via libx264 directly, so grain only
+            // ever needs the noise-filter fallback, not SVT-AV1's native param.
+            if let Some(noise) = film_grain::noise_filter_fallback(clip_grain_strength(&clip.filters)) {
+                vf_parts.push(noise);
             }
-            
+
+            let speed_segments = clip.speed_segments.clone().unwrap_or_default();
+            if !speed_segments.is_empty() {
+                speed_ramp::validate_segments(&speed_segments, clip.duration)?;
+            }
+
+            if speed_segments.is_empty() {
+                if !vf_parts.is_empty() {
+                    cmd.arg("-vf").arg(vf_parts.join(","));
+                }
+            } else {
+                let mut filter_complex = speed_ramp::build_filter_complex(
+                    "0:v", "0:a", &speed_segments, clip.duration, "vramp", "aout",
+                );
+                let vout_label = if vf_parts.is_empty() {
+                    "vramp"
+                } else {
+                    filter_complex.push_str(&format!(";[vramp]{}[vout]", vf_parts.join(",")));
+                    "vout"
+                };
+                cmd.arg("-filter_complex").arg(filter_complex);
+                cmd.arg("-map").arg(format!("[{}]", vout_label));
+                cmd.arg("-map").arg("[aout]");
+            }
+
             cmd.arg("-hide_banner");
             cmd.arg("-loglevel").arg("error");
             cmd.arg(temp_file.to_str().unwrap()); // Output file - this was missing!
@@ -1382,8 +2067,17 @@ fn export_video_blocking(
         cmd.arg("-crf").arg(&crf);
         cmd.arg("-c:a").arg("aac");
         cmd.arg("-b:a").arg("192k");
-        cmd.arg("-pix_fmt").arg("yuv420p");
-        
+        // Track 0's own per-clip temp files above already carry the right
+        // pix_fmt/color tags; this concat is a stream of same-sized frames,
+        // so the first clip's HDR-ness decides the whole base video's.
+        let base_hdr_decision = resolve_hdr(&track_0_clips[0].file_path);
+        cmd.arg("-pix_fmt").arg(base_hdr_decision.pix_fmt);
+        for arg in &base_hdr_decision.color_args {
+            cmd.arg(arg);
+        }
+        cmd.arg("-r").arg(project_fps.as_ffmpeg_arg());
+        cmd.arg("-video_track_timescale").arg(project_fps.num.to_string());
+
         // Pad base video to max duration if needed
         if max_duration > 0.0 {
             cmd.arg("-t").arg(&format!("{:.3}", max_duration));
@@ -1420,94 +2114,111 @@ fn export_video_blocking(
             return Err("Base video file was not created".to_string());
         }
         
-        // Step 2: Build overlay video from Track 1
-        let mut overlay1_video: Option<std::path::PathBuf> = None;
-        // For now, handle single overlay clip on Track 1
-        // TODO: Handle multiple overlay clips on same track (need to chain overlays with proper timing)
-        if track_1_clips.len() == 1 {
-                let clip = &track_1_clips[0];
-                let overlay_path = clip.file_path.replace("\\", "/");
-                let overlay_temp = temp_dir.join("overlay1.mp4");
-                
-                // Scale overlay to be 30% of base size
-                let overlay_w = if width > 0 { (width as f64 * 0.3) as u32 } else { 320 };
-                let overlay_h = if height > 0 { (height as f64 * 0.3) as u32 } else { 180 };
-                
-                // Create overlay video padded to full timeline duration with clip at correct offset
-                let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-                cmd.arg("-y");
-                // Create black background for full duration
-                cmd.arg("-f").arg("lavfi");
-                cmd.arg("-i").arg(format!("color=c=black:s={}x{}:d={:.3}", overlay_w, overlay_h, max_duration));
-                // Add the source video
-                cmd.arg("-ss").arg(&format!("{:.3}", clip.trim_start));
-                cmd.arg("-i").arg(&overlay_path);
-                cmd.arg("-t").arg(&format!("{:.3}", clip.duration));
-                
-                // Filter to scale overlay and position it at the clip's timeline offset
-                let offset = clip.start_time;
-                let filter = format!(
-                    "[1:v]scale={}:{}[scaled];[0:v][scaled]overlay=enable='between(t,{:.3},{:.3})'[vout]",
-                    overlay_w, overlay_h, offset, offset + clip.duration
-                );
-                
-                cmd.arg("-filter_complex").arg(&filter);
-                cmd.arg("-map").arg("[vout]");
-                cmd.arg("-c:v").arg("libx264");
-                cmd.arg("-preset").arg(&preset);
-                cmd.arg("-crf").arg(&crf);
-                cmd.arg("-pix_fmt").arg("yuv420p");
-                cmd.arg("-t").arg(&format!("{:.3}", max_duration));
-                cmd.arg("-hide_banner");
-                cmd.arg("-loglevel").arg("error");
-                cmd.arg(overlay_temp.to_str().unwrap()); // Output file
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::piped());
-                
-                let output = cmd.output()
-                    .map_err(|e| format!("Failed to execute FFmpeg for overlay 1: {}", e))?;
-                
-                if output.status.success() && overlay_temp.exists() {
-                    overlay1_video = Some(overlay_temp);
-                } else {
-                    let error_msg = String::from_utf8_lossy(&output.stderr);
-                    return Err(format!("Failed to create overlay 1 video file: FFmpeg error: {}", error_msg));
-                }
-        }
-        
-        // Step 3: Build final composite
+        // Step 2+3: Build a single filter_complex graph straight from each
+        // overlay clip's own source file against the base video - no more
+        // per-clip intermediate padded video. Overlays are sorted by (track,
+        // start_time) so later tracks always land on top of earlier ones
+        // and, within a track, clips chain in timeline order; each clip gets
+        // its own scale + `overlay=x:y:enable='between(t,start,end)'` node
+        // instead of one track-wide position/window.
+        let mut overlay_clips: Vec<&ClipData> = overlay_track_clips.clone();
+        overlay_clips.sort_by(|a, b| {
+            a.track.cmp(&b.track).then(a.start_time.partial_cmp(&b.start_time).unwrap())
+        });
+
+        // VAAPI's GPU-compositing fast-path (`overlay_vaapi`) only replaces a
+        // single base+overlay pair, so it's only offered when there's exactly
+        // one overlay clip to chain - everything else runs on the CPU `overlay`
+        // filter, same as before this path existed.
+        let vaapi_overlay_plan = if overlay_clips.len() == 1 {
+            encoder_backend::vaapi_overlay_plan(
+                encoder.unwrap_or(encoder_backend::EncoderBackend::Auto),
+                &crf,
+                &preset,
+                &ffmpeg_path,
+            )
+        } else {
+            None
+        };
+
         let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
         cmd.arg("-y");
+        if let Some(ref plan) = vaapi_overlay_plan {
+            for arg in &plan.input_args {
+                cmd.arg(arg);
+            }
+        }
         cmd.arg("-i").arg(base_video.to_str().unwrap());
-        
+
+        // Each overlay clip is its own FFmpeg input, trimmed to its own
+        // `[trim_start, trim_start+duration)` window directly - the base
+        // video's duration already covers the whole timeline, and
+        // `setpts`/`enable` below (not input padding) place the clip at its
+        // correct offset and visibility window.
+        for clip in &overlay_clips {
+            let overlay_path = clip.file_path.replace("\\", "/");
+            if clip.trim_start > 0.0 {
+                cmd.arg("-ss").arg(&format!("{:.3}", clip.trim_start));
+            }
+            cmd.arg("-i").arg(&overlay_path);
+            if clip.duration > 0.0 {
+                cmd.arg("-t").arg(&format!("{:.3}", clip.duration));
+            }
+        }
+
         let mut filter_parts = Vec::new();
-        
-        // Add Track 1 overlay
-        if let Some(ref overlay1) = overlay1_video {
-            cmd.arg("-i").arg(overlay1.to_str().unwrap());
-            
-            // Calculate overlay position from configuration
+        let mut current_label = "0:v".to_string();
+        for (i, clip) in overlay_clips.iter().enumerate() {
+            let input_idx = i + 1;
             let overlay_w = (width.max(640) as f64 * 0.3) as u32;
             let overlay_h = (height.max(480) as f64 * 0.3) as u32;
             let position_str = overlay_positions.as_ref()
-                .and_then(|p| p.track1.as_deref())
+                .and_then(|p| p.get(&clip.track))
+                .map(|s| s.as_str())
                 .unwrap_or("bottom-right");
             let (x_pos, y_pos) = calculate_overlay_pos(position_str, width.max(640), height.max(480), overlay_w, overlay_h, 20);
-            
-            if track_1_clips.len() == 1 {
-                // Overlay is already positioned and timed in the overlay video itself
-                // Just overlay it at bottom-right position
+
+            // Scale this clip's own stream and shift its timestamps forward by
+            // its timeline offset, so it lines up against the base video's
+            // timeline instead of starting from t=0 like its own source does.
+            let scaled_label = format!("ov{}", i);
+            filter_parts.push(format!(
+                "[{input}:v]scale={w}:{h},setpts=PTS+{offset:.3}/TB[{label}]",
+                input = input_idx,
+                w = overlay_w,
+                h = overlay_h,
+                offset = clip.start_time,
+                label = scaled_label,
+            ));
+
+            let out_label = if i + 1 == overlay_clips.len() {
+                "vout".to_string()
+            } else {
+                format!("vstage{}", i)
+            };
+            if vaapi_overlay_plan.is_some() {
+                filter_parts.push(encoder_backend::vaapi_overlay_filter(
+                    &current_label, &scaled_label, x_pos, y_pos, &out_label,
+                ));
+            } else {
+                let enable_window = format!("between(t,{:.3},{:.3})", clip.start_time, clip.start_time + clip.duration);
                 filter_parts.push(format!(
-                    "[0:v][1:v]overlay={}:{}[vout]",
-                    x_pos, y_pos
+                    "[{base}][{label}]overlay={x}:{y}:enable='{enable}'[{out}]",
+                    base = current_label,
+                    label = scaled_label,
+                    x = x_pos,
+                    y = y_pos,
+                    enable = enable_window,
+                    out = out_label,
                 ));
             }
-        } else {
+            current_label = out_label;
+        }
+
+        if overlay_clips.is_empty() {
             filter_parts.push("[0:v]copy[vout]".to_string());
         }
-        
-        // TODO: Add Track 2 overlay similarly
-        
+
         // Add subtitle burn-in if SRT file provided
         let mut final_filter_complex = if !filter_parts.is_empty() {
             filter_parts.join(";")
@@ -1516,31 +2227,18 @@ fn export_video_blocking(
         };
         
         if let Some(ref srt_path) = subtitleSrtPath {
-            println!("ðŸŽ¬ Burning subtitles from: {}", srt_path);
-            
-            if !std::path::Path::new(srt_path).exists() {
-                return Err(format!("Subtitle file not found: {}", srt_path));
-            }
-            
-            // Read SRT content
-            let srt_content = std::fs::read_to_string(srt_path)
-                .map_err(|e| format!("Failed to read SRT: {}", e))?;
-            
-            println!("SRT content ({} bytes)", srt_content.len());
-            
-            // WORKAROUND: Copy SRT to current directory (no drive letter path issues)
-            let simple_srt = std::path::PathBuf::from("./temp_subtitles.srt");
-            
-            std::fs::copy(srt_path, &simple_srt)
-                .map_err(|e| format!("Failed to copy SRT: {}", e))?;
-            
-            println!("Copied SRT to: {:?}", simple_srt);
-            
-            // Use relative path - no drive letter issues!
-            let subtitle_filter_str = "subtitles=./temp_subtitles.srt".to_string();
-            
-            println!("Subtitle filter: {}", subtitle_filter_str);
-            
+            // Speed-ramped clips aren't remapped into this branch's subtitle
+            // burn-in (the overlay compositing path doesn't track the
+            // per-clip timeline spans `speed_ramp::remap_time` needs the way
+            // the plain multi-clip path above does), so captions stay on the
+            // original, pre-ramp timeline here.
+            let subtitle_filter_str = prepare_subtitle_filter(
+                srt_path,
+                subtitle_style_preset.unwrap_or_default(),
+                subtitle_karaoke.unwrap_or(false),
+                None,
+            )?;
+
             if !final_filter_complex.is_empty() {
                 // Add subtitles filter after overlay processing
                 final_filter_complex = format!("{};[vout]{}[vsub]", 
@@ -1559,14 +2257,24 @@ fn export_video_blocking(
             cmd.arg("-map").arg("0:a?"); // Map audio from base video if present
         }
         
-        cmd.arg("-c:v").arg("libx264");
-        cmd.arg("-preset").arg(&preset);
-        cmd.arg("-crf").arg(&crf);
+        if let Some(ref plan) = vaapi_overlay_plan {
+            for arg in &plan.codec_args {
+                cmd.arg(arg);
+            }
+        } else {
+            cmd.arg("-c:v").arg("libx264");
+            cmd.arg("-preset").arg(&preset);
+            cmd.arg("-crf").arg(&crf);
+        }
         cmd.arg("-c:a").arg("aac");
         cmd.arg("-b:a").arg("192k");
-        cmd.arg("-pix_fmt").arg("yuv420p");
+        cmd.arg("-pix_fmt").arg(base_hdr_decision.pix_fmt);
+        for arg in &base_hdr_decision.color_args {
+            cmd.arg(arg);
+        }
+        cmd.arg("-r").arg(project_fps.as_ffmpeg_arg());
         cmd.arg("-t").arg(&format!("{:.3}", max_duration));
-        cmd.arg("-movflags").arg("faststart");
+        cmd.arg("-movflags").arg(streaming_format_choice.movflags());
         cmd.arg("-hide_banner");
         // Use error level if subtitles are present for debugging
         if subtitleSrtPath.is_some() {
@@ -1594,11 +2302,10 @@ fn export_video_blocking(
                 }
                 let _ = std::fs::remove_file(&track0_concat);
                 let _ = std::fs::remove_file(&base_video);
-                if let Some(overlay1) = overlay1_video {
-                    let _ = std::fs::remove_file(overlay1);
-                }
                 let _ = std::fs::write("src-tauri/export_debug.log", "=== MULTI-TRACK EXPORT SUCCESS ===\n");
-                return Ok(format!("Multi-track video exported successfully to {}", outputPath));
+                let backend_note = if vaapi_overlay_plan.is_some() { " (VAAPI GPU compositing)" } else { "" };
+                let streaming_note = finalize_streaming_format(&outputPath, output_codec::OutputCodec::Libx264);
+                return Ok(format!("Multi-track video exported successfully to {}{}{}", outputPath, backend_note, streaming_note));
             } else {
                 // Cleanup temp files on failure
                 for f in &track_0_temp_files {
@@ -1606,9 +2313,6 @@ fn export_video_blocking(
                 }
                 let _ = std::fs::remove_file(&track0_concat);
                 let _ = std::fs::remove_file(&base_video);
-                if let Some(overlay1) = overlay1_video {
-                    let _ = std::fs::remove_file(overlay1);
-                }
                 let _ = std::fs::write("src-tauri/export_debug.log", "=== MULTI-TRACK EXPORT FAILED ===\n");
                 return Err("Failed to composite multi-track video".to_string());
             }
@@ -1624,9 +2328,6 @@ fn export_video_blocking(
             }
             let _ = std::fs::remove_file(&track0_concat);
             let _ = std::fs::remove_file(&base_video);
-            if let Some(overlay1) = overlay1_video {
-                let _ = std::fs::remove_file(overlay1);
-            }
             return Err(format!("FFmpeg multi-track failed: {}", error_msg));
         }
         
@@ -1638,9 +2339,6 @@ fn export_video_blocking(
         }
         let _ = std::fs::remove_file(&track0_concat);
         let _ = std::fs::remove_file(&base_video);
-        if let Some(overlay1) = overlay1_video {
-            let _ = std::fs::remove_file(overlay1);
-        }
         
         if status.success() {
             // Log success with subtitle info
@@ -1654,7 +2352,9 @@ fn export_video_blocking(
             } else {
                 let _ = std::fs::write("src-tauri/export_debug.log", "=== MULTI-TRACK EXPORT SUCCESS ===\n");
             }
-            Ok(format!("Multi-track video exported successfully to {}", outputPath))
+            let backend_note = if vaapi_overlay_plan.is_some() { " (VAAPI GPU compositing)" } else { "" };
+            let streaming_note = finalize_streaming_format(&outputPath, output_codec::OutputCodec::Libx264);
+            Ok(format!("Multi-track video exported successfully to {}{}{}", outputPath, backend_note, streaming_note))
         } else {
             let _ = std::fs::write("src-tauri/export_debug.log", "=== MULTI-TRACK EXPORT FAILED ===\n");
             Err("Failed to composite multi-track video".to_string())
@@ -1665,12 +2365,13 @@ fn export_video_blocking(
 #[tauri::command]
 fn import_video_file(
     app_handle: tauri::AppHandle,
-    file_path: String
+    file_path: String,
+    encoder: Option<encoder_backend::EncoderBackend>,
 ) -> Result<String, String> {
     // Check if it's a MOV file
     if file_path.to_lowercase().ends_with(".mov") {
         println!("MOV file detected, converting for compatibility...");
-        
+
         // Create a temporary MP4 version
         let temp_dir = std::env::temp_dir();
         let file_name = std::path::Path::new(&file_path)
@@ -1679,28 +2380,37 @@ fn import_video_file(
             .unwrap_or("converted");
         let output_path = temp_dir.join(format!("{}_converted.mp4", file_name));
         let output_path_str = output_path.to_string_lossy().to_string();
-        
-        let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
-        cmd.arg("-y");
-        cmd.arg("-i").arg(&file_path);
-        
-        // Force re-encode to ensure compatibility
-        cmd.arg("-c:v").arg("libx264");
-        cmd.arg("-preset").arg("medium"); // Better compatibility than "fast"
-        cmd.arg("-crf").arg("23");
-        cmd.arg("-pix_fmt").arg("yuv420p"); // Ensure compatible pixel format
-        cmd.arg("-c:a").arg("aac");
-        cmd.arg("-b:a").arg("192k");
-        cmd.arg("-movflags").arg("faststart");
-        cmd.arg(&output_path_str);
-        cmd.arg("-hide_banner");
-        cmd.arg("-loglevel").arg("warning");
-        cmd.stdout(Stdio::null());
-        cmd.stderr(Stdio::piped());
-        
-        let output = cmd.output()
-            .map_err(|e| format!("Failed to convert MOV: {}", e))?;
-        
+
+        let requested = encoder.unwrap_or(encoder_backend::EncoderBackend::Auto);
+        let ffmpeg_path = find_ffmpeg_binary(Some(&app_handle), "ffmpeg");
+        let output = encoder_backend::run_with_hardware_fallback(requested, "23", "medium", &ffmpeg_path, |plan| {
+            let mut cmd = create_hidden_command(Some(&app_handle), "ffmpeg");
+            cmd.arg("-y");
+            for arg in &plan.input_args {
+                cmd.arg(arg);
+            }
+            cmd.arg("-i").arg(&file_path);
+
+            if let Some(suffix) = plan.filter_suffix {
+                cmd.arg("-vf").arg(suffix);
+            }
+
+            for arg in &plan.codec_args {
+                cmd.arg(arg);
+            }
+            cmd.arg("-pix_fmt").arg("yuv420p"); // Ensure compatible pixel format
+            cmd.arg("-c:a").arg("aac");
+            cmd.arg("-b:a").arg("192k");
+            cmd.arg("-movflags").arg("faststart");
+            cmd.arg(&output_path_str);
+            cmd.arg("-hide_banner");
+            cmd.arg("-loglevel").arg("warning");
+            cmd.stdout(Stdio::null());
+            cmd.stderr(Stdio::piped());
+
+            cmd.output().map_err(|e| format!("Failed to convert MOV: {}", e))
+        })?;
+
         if output.status.success() {
             // Wait for file to be fully written and flushed
             std::thread::sleep(std::time::Duration::from_millis(500));
@@ -1742,6 +2452,7 @@ pub fn run() {
         test_export, 
         save_temp_video, 
         export_video,
+        export_hls,
         get_temp_dir,
         get_file_size,
         get_video_duration_from_file,
@@ -1752,6 +2463,21 @@ pub fn run() {
         start_screen_recording_async,
         stop_screen_recording_async,
         get_recording_status,
+        start_replay_buffer,
+        stop_replay_buffer,
+        save_replay,
+        start_caption_chunking,
+        stop_caption_chunking,
+        start_dual_audio_capture,
+        start_audio_capture_opus,
+        get_audio_levels,
+        list_encoders,
+        cancel_export,
+        export_streaming,
+        start_streaming_async,
+        stop_streaming_async,
+        split_recording_into_scenes,
+        run_export_pipeline,
         mux_video_audio,
         convert_webm_to_mp4,
         composite_pip_video,
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+/// Final delivery codec for `export_video`, independent of the hardware
+/// acceleration backend in `encoder_backend` (which is only used when this is
+/// `Libx264`). Defaults to `Libx264` when not specified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputCodec {
+    Libx264,
+    Libx265,
+    Vp9,
+    Av1,
+}
+
+impl OutputCodec {
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            OutputCodec::Libx264 => "libx264",
+            OutputCodec::Libx265 => "libx265",
+            OutputCodec::Vp9 => "libvpx-vp9",
+            OutputCodec::Av1 => "libsvtav1",
+        }
+    }
+
+    /// Containers (lowercase extension, no dot) this codec can be muxed into.
+    fn compatible_containers(&self) -> &'static [&'static str] {
+        match self {
+            OutputCodec::Libx264 | OutputCodec::Libx265 => &["mp4", "mov"],
+            OutputCodec::Vp9 | OutputCodec::Av1 => &["webm", "mp4"],
+        }
+    }
+
+    /// `-tag:v` fourcc some players need to recognize VP9/AV1 in an MP4
+    /// container (without it, a few players show a black frame).
+    fn mp4_tag(&self) -> Option<&'static str> {
+        match self {
+            OutputCodec::Vp9 => Some("vp09"),
+            OutputCodec::Av1 => Some("av01"),
+            _ => None,
+        }
+    }
+
+    /// `-c:v <name>` plus this codec's rate-control flags for a final,
+    /// full-quality pass, plus an MP4 tag if the container needs one.
+    pub fn final_args(&self, crf: &str, preset: &str, container: &str) -> Vec<String> {
+        let mut args = vec!["-c:v".to_string(), self.ffmpeg_name().to_string()];
+        match self {
+            OutputCodec::Libx264 | OutputCodec::Libx265 => {
+                args.push("-preset".into());
+                args.push(preset.into());
+                args.push("-crf".into());
+                args.push(crf.into());
+            }
+            OutputCodec::Vp9 => {
+                // b:v 0 puts libvpx-vp9 into constant-quality (CRF-only) mode.
+                args.push("-crf".into());
+                args.push(crf.into());
+                args.push("-b:v".into());
+                args.push("0".into());
+                args.push("-deadline".into());
+                args.push(vp9_deadline(preset).to_string());
+            }
+            OutputCodec::Av1 => {
+                args.push("-crf".into());
+                args.push(crf.into());
+                args.push("-preset".into());
+                args.push(svtav1_preset_number(preset).to_string());
+            }
+        }
+
+        if container == "mp4" {
+            if let Some(tag) = self.mp4_tag() {
+                args.push("-tag:v".into());
+                args.push(tag.into());
+            }
+        }
+
+        args
+    }
+
+    /// Fast, high-bitrate libx264 settings for an intermediate compositing
+    /// pass (the multi-clip export's per-clip trim), always libx264 regardless of
+    /// the requested final codec. Since these files are immediately
+    /// re-encoded by the later final-codec pass, there's no reason to pay for
+    /// the final codec's encode cost twice, or to compress hard enough here
+    /// to stack generation loss on top of the final pass.
+    pub fn intermediate_args() -> Vec<String> {
+        vec![
+            "-c:v".into(), "libx264".into(),
+            "-preset".into(), "ultrafast".into(),
+            "-crf".into(), "12".into(),
+        ]
+    }
+}
+
+/// Map this repo's libx264-style preset names onto libvpx-vp9's `-deadline`.
+fn vp9_deadline(preset: &str) -> &'static str {
+    match preset {
+        "ultrafast" | "superfast" | "veryfast" | "faster" | "fast" => "realtime",
+        _ => "good",
+    }
+}
+
+/// Map this repo's libx264-style preset names onto SVT-AV1's `-preset`
+/// integer scale (0 = slowest/best, 13 = fastest).
+fn svtav1_preset_number(preset: &str) -> u32 {
+    match preset {
+        "ultrafast" => 12,
+        "superfast" => 11,
+        "veryfast" => 10,
+        "faster" => 9,
+        "fast" => 8,
+        "medium" => 6,
+        "slow" => 4,
+        "slower" => 3,
+        "veryslow" => 2,
+        _ => 6,
+    }
+}
+
+/// Validate that `codec` can be written into the container implied by
+/// `output_path`'s extension, before any FFmpeg process is spawned, so a bad
+/// combination fails with a descriptive error instead of an opaque muxer
+/// error partway through an export.
+pub fn validate_codec_container(codec: OutputCodec, output_path: &str) -> Result<(), String> {
+    let container = container_from_path(output_path)?;
+    if codec.compatible_containers().contains(&container.as_str()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{:?} cannot be exported to .{} - use one of: .{}",
+            codec,
+            container,
+            codec.compatible_containers().join(", .")
+        ))
+    }
+}
+
+/// Lowercased file extension (no leading dot) from an output path.
+fn container_from_path(output_path: &str) -> Result<String, String> {
+    std::path::Path::new(output_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .ok_or_else(|| format!("Output path has no recognizable extension: {}", output_path))
+}
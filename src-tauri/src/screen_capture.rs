@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::process::{Child, Command, Stdio};
 
@@ -9,6 +10,52 @@ pub struct ScreenSource {
     pub is_primary: bool,
     pub width: u32,
     pub height: u32,
+    pub x: i32, // Origin of this monitor's rcMonitor in virtual desktop coordinates
+    pub y: i32,
+}
+
+/// An optional crop rectangle within a `ScreenSource`, in that monitor's own
+/// coordinate space (0,0 = the monitor's top-left corner).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Video encoder requested for a recording session. A hardware encoder that FFmpeg
+/// doesn't report falls back to software x264 (see `resolve_encoder`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VideoEncoder {
+    Libx264,
+    H264Nvenc,
+    HevcNvenc,
+    H264Qsv,
+    H264Amf,
+}
+
+impl VideoEncoder {
+    /// The literal `-c:v` name FFmpeg expects for this encoder.
+    fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            VideoEncoder::Libx264 => "libx264",
+            VideoEncoder::H264Nvenc => "h264_nvenc",
+            VideoEncoder::HevcNvenc => "hevc_nvenc",
+            VideoEncoder::H264Qsv => "h264_qsv",
+            VideoEncoder::H264Amf => "h264_amf",
+        }
+    }
+}
+
+/// Which capture mode a `RecordingSession` is running in: writing straight to a file,
+/// or feeding a rolling segment buffer that `save_replay` can snapshot on demand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingMode {
+    Record,
+    Replay,
 }
 
 #[derive(Debug)]
@@ -19,6 +66,9 @@ pub struct RecordingSession {
     pub ffmpeg_process: Option<u32>, // Store process ID
     pub audio_start_time: Option<std::time::Instant>, // When audio capture actually started
     pub video_start_time: Option<std::time::Instant>, // When video capture actually started
+    pub encoder: VideoEncoder, // Encoder actually in use for this session (after fallback)
+    pub mode: RecordingMode,
+    pub replay_buffer_seconds: u32, // Length of the rolling buffer in Replay mode
 }
 
 lazy_static::lazy_static! {
@@ -29,8 +79,107 @@ lazy_static::lazy_static! {
         ffmpeg_process: None,
         audio_start_time: None,
         video_start_time: None,
+        encoder: VideoEncoder::Libx264,
+        mode: RecordingMode::Record,
+        replay_buffer_seconds: 0,
     }));
     static ref FFMPEG_CHILD: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
+    // Cache of `-c:v` names FFmpeg reports as available, populated on first use.
+    static ref AVAILABLE_ENCODERS: Arc<Mutex<Option<HashSet<String>>>> = Arc::new(Mutex::new(None));
+    // Directory holding the rolling segments for the active replay buffer, if any.
+    static ref REPLAY_BUFFER_DIR: Arc<Mutex<Option<std::path::PathBuf>>> = Arc::new(Mutex::new(None));
+}
+
+/// Where a recording's output goes: a local file (the original behavior), or a live
+/// streaming ingest endpoint reached via RTMP or SRT.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "url")]
+pub enum OutputTarget {
+    File(String),
+    Rtmp(String),
+    Srt(String),
+}
+
+/// How many 1-second segments we keep in the rolling replay buffer for a given
+/// buffer length. One extra segment is kept as slack so `save_replay` always has
+/// a full window to concatenate even if the wrap just rotated.
+fn replay_segment_wrap(buffer_seconds: u32) -> u32 {
+    buffer_seconds.max(1) + 1
+}
+
+/// Run `ffmpeg -encoders` once and cache the set of encoder names it reports.
+/// Subsequent calls reuse the cached set instead of spawning FFmpeg again.
+fn detect_available_encoders() -> HashSet<String> {
+    let mut cache = AVAILABLE_ENCODERS.lock().unwrap();
+    if let Some(ref encoders) = *cache {
+        return encoders.clone();
+    }
+
+    let mut encoders = HashSet::new();
+    if let Ok(output) = Command::new("ffmpeg").arg("-hide_banner").arg("-encoders").output() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            // Lines look like: " V..... h264_nvenc           NVIDIA NVENC H.264 encoder"
+            let trimmed = line.trim_start();
+            if !trimmed.starts_with('V') {
+                continue;
+            }
+            if let Some(name) = trimmed.split_whitespace().nth(1) {
+                encoders.insert(name.to_string());
+            }
+        }
+    }
+
+    *cache = Some(encoders.clone());
+    encoders
+}
+
+/// Resolve the requested encoder against what FFmpeg actually supports,
+/// falling back to libx264 if the requested hardware encoder is missing.
+fn resolve_encoder(requested: VideoEncoder) -> VideoEncoder {
+    if requested == VideoEncoder::Libx264 {
+        return requested;
+    }
+
+    let available = detect_available_encoders();
+    if available.contains(requested.ffmpeg_name()) {
+        requested
+    } else {
+        println!(
+            "Requested encoder {:?} not available, falling back to libx264",
+            requested
+        );
+        VideoEncoder::Libx264
+    }
+}
+
+/// Build the codec-specific FFmpeg args (`-c:v` plus rate-control flags) for an encoder.
+fn encoder_args(encoder: VideoEncoder) -> Vec<String> {
+    match encoder {
+        VideoEncoder::Libx264 => vec![
+            "-c:v".into(), "libx264".into(),
+            "-preset".into(), "ultrafast".into(),
+            "-crf".into(), "23".into(),
+        ],
+        VideoEncoder::H264Nvenc | VideoEncoder::HevcNvenc => vec![
+            "-c:v".into(), encoder.ffmpeg_name().into(),
+            "-preset".into(), "p4".into(),
+            "-rc".into(), "vbr".into(),
+            "-cq".into(), "23".into(),
+        ],
+        VideoEncoder::H264Qsv => vec![
+            "-c:v".into(), "h264_qsv".into(),
+            "-preset".into(), "fast".into(),
+            "-global_quality".into(), "23".into(),
+        ],
+        VideoEncoder::H264Amf => vec![
+            "-c:v".into(), "h264_amf".into(),
+            "-quality".into(), "balanced".into(),
+            "-rc".into(), "cqp".into(),
+            "-qp_i".into(), "23".into(),
+            "-qp_p".into(), "23".into(),
+        ],
+    }
 }
 
 #[cfg(windows)]
@@ -75,6 +224,8 @@ pub fn list_screen_sources() -> Result<Vec<ScreenSource>, String> {
                         is_primary,
                         width,
                         height,
+                        x: info.rcMonitor.left,
+                        y: info.rcMonitor.top,
                     });
                 }
                 
@@ -98,12 +249,20 @@ pub fn list_screen_sources() -> Result<Vec<ScreenSource>, String> {
             is_primary: true,
             width: 1920,
             height: 1080,
+            x: 0,
+            y: 0,
         });
     }
-    
+
     Ok(sources)
 }
 
+/// Find a previously-enumerated source by its `id`.
+#[cfg(windows)]
+fn find_screen_source(source_id: &str) -> Option<ScreenSource> {
+    list_screen_sources().ok()?.into_iter().find(|s| s.id == source_id)
+}
+
 #[cfg(not(windows))]
 pub fn list_screen_sources() -> Result<Vec<ScreenSource>, String> {
     Err("Screen capture is only supported on Windows".to_string())
@@ -111,17 +270,54 @@ pub fn list_screen_sources() -> Result<Vec<ScreenSource>, String> {
 
 #[cfg(windows)]
 pub fn start_screen_recording_process(output_path: String) -> Result<String, String> {
+    start_screen_recording_process_with_encoder(output_path, VideoEncoder::Libx264)
+}
+
+#[cfg(windows)]
+pub fn start_screen_recording_process_with_encoder(
+    output_path: String,
+    requested_encoder: VideoEncoder,
+) -> Result<String, String> {
+    start_screen_recording_process_full(output_path, requested_encoder, None, None)
+}
+
+/// Start recording a specific monitor (or the whole virtual desktop when `source_id`
+/// is `None`), optionally cropped to `region` within that monitor.
+#[cfg(windows)]
+pub fn start_screen_recording_process_full(
+    output_path: String,
+    requested_encoder: VideoEncoder,
+    source_id: Option<String>,
+    region: Option<CaptureRegion>,
+) -> Result<String, String> {
     let mut session = RECORDING_SESSION.lock().unwrap();
-    
+
     if session.is_recording {
         return Err("Recording already in progress".to_string());
     }
-    
+
     // Validate output path
     if !output_path.ends_with(".mp4") {
         return Err("Output path must end with .mp4".to_string());
     }
-    
+
+    let encoder = resolve_encoder(requested_encoder);
+    println!("Using video encoder: {:?}", encoder);
+
+    // Resolve the requested monitor (if any) to its virtual-desktop origin and size,
+    // then translate that plus the optional crop region into gdigrab offset/size args.
+    let source = source_id.as_deref().and_then(find_screen_source);
+    let (offset_x, offset_y, video_size) = match (&source, region) {
+        (Some(src), Some(region)) => (
+            src.x + region.x as i32,
+            src.y + region.y as i32,
+            Some((region.width, region.height)),
+        ),
+        (Some(src), None) => (src.x, src.y, Some((src.width, src.height))),
+        (None, Some(region)) => (region.x as i32, region.y as i32, Some((region.width, region.height))),
+        (None, None) => (0, 0, None),
+    };
+
     // Start video recording FIRST (before audio)
     // This way we minimize the delay between them
     let mut cmd = Command::new("ffmpeg");
@@ -129,10 +325,15 @@ pub fn start_screen_recording_process(output_path: String) -> Result<String, Str
     cmd.arg("-f").arg("gdigrab");
     cmd.arg("-draw_mouse").arg("0");
     cmd.arg("-framerate").arg("30");
+    if offset_x != 0 || offset_y != 0 {
+        cmd.arg("-offset_x").arg(offset_x.to_string());
+        cmd.arg("-offset_y").arg(offset_y.to_string());
+    }
+    if let Some((w, h)) = video_size {
+        cmd.arg("-video_size").arg(format!("{}x{}", w, h));
+    }
     cmd.arg("-i").arg("desktop");
-    cmd.arg("-c:v").arg("libx264");
-    cmd.arg("-preset").arg("ultrafast");
-    cmd.arg("-crf").arg("23");
+    cmd.args(encoder_args(encoder));
     cmd.arg("-pix_fmt").arg("yuv420p");
     cmd.arg("-movflags").arg("faststart");
     
@@ -177,6 +378,7 @@ pub fn start_screen_recording_process(output_path: String) -> Result<String, Str
     session.ffmpeg_process = Some(pid);
     session.audio_start_time = if has_audio { Some(audio_start) } else { None };
     session.video_start_time = Some(video_start);
+    session.encoder = encoder;
     
     // Calculate and log the delay
     let delay_ms = audio_start.duration_since(video_start).as_millis();
@@ -196,6 +398,131 @@ pub fn start_screen_recording_process(_output_path: String) -> Result<String, St
     Err("Screen capture is only supported on Windows".to_string())
 }
 
+#[cfg(not(windows))]
+pub fn start_screen_recording_process_with_encoder(
+    _output_path: String,
+    _requested_encoder: VideoEncoder,
+) -> Result<String, String> {
+    Err("Screen capture is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn start_screen_recording_process_full(
+    _output_path: String,
+    _requested_encoder: VideoEncoder,
+    _source_id: Option<String>,
+    _region: Option<CaptureRegion>,
+) -> Result<String, String> {
+    Err("Screen capture is only supported on Windows".to_string())
+}
+
+/// Start recording to an `OutputTarget`. `File` reuses the normal split-and-mux
+/// pipeline; `Rtmp`/`Srt` instead build a single live FFmpeg process that takes
+/// video from gdigrab and audio from a piped `s16le` input, muxed on the fly.
+#[cfg(windows)]
+pub fn start_streaming_process(
+    target: OutputTarget,
+    requested_encoder: VideoEncoder,
+) -> Result<String, String> {
+    let (container_fmt, url) = match target {
+        OutputTarget::File(path) => {
+            return start_screen_recording_process_full(path, requested_encoder, None, None);
+        }
+        OutputTarget::Rtmp(url) => ("flv", url),
+        OutputTarget::Srt(url) => ("mpegts", url),
+    };
+
+    let mut session = RECORDING_SESSION.lock().unwrap();
+    if session.is_recording {
+        return Err("Recording already in progress".to_string());
+    }
+
+    let encoder = resolve_encoder(requested_encoder);
+    println!("Streaming to {} via {} (encoder: {:?})", url, container_fmt, encoder);
+
+    const STREAM_SAMPLE_RATE: u32 = 48000;
+    const STREAM_CHANNELS: u16 = 2;
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.arg("-y");
+    cmd.arg("-f").arg("gdigrab");
+    cmd.arg("-draw_mouse").arg("0");
+    cmd.arg("-framerate").arg("30");
+    cmd.arg("-i").arg("desktop");
+    // Second input: live WASAPI loopback audio piped in as raw interleaved PCM.
+    cmd.arg("-f").arg("s16le");
+    cmd.arg("-ar").arg(STREAM_SAMPLE_RATE.to_string());
+    cmd.arg("-ac").arg(STREAM_CHANNELS.to_string());
+    cmd.arg("-i").arg("pipe:0");
+    cmd.args(encoder_args(encoder));
+    cmd.arg("-pix_fmt").arg("yuv420p");
+    cmd.arg("-c:a").arg("aac");
+    cmd.arg("-b:a").arg("192k");
+    cmd.arg("-f").arg(container_fmt);
+    cmd.arg(&url);
+    cmd.arg("-hide_banner");
+    cmd.arg("-loglevel").arg("warning");
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd.spawn()
+        .map_err(|e| format!("Failed to start FFmpeg: {}. Make sure FFmpeg is installed and in PATH.", e))?;
+    let pid = child.id();
+
+    let stdin = child.stdin.take()
+        .ok_or("Failed to open FFmpeg stdin for piped audio")?;
+
+    // Feed live loopback audio straight into FFmpeg's stdin; there is no separate
+    // WAV file or post-hoc mux step for streaming targets.
+    if let Err(e) = super::audio_capture::start_audio_capture_piped(stdin, STREAM_SAMPLE_RATE, STREAM_CHANNELS) {
+        let _ = child.kill();
+        return Err(format!("Failed to start piped audio capture: {}", e));
+    }
+
+    *FFMPEG_CHILD.lock().unwrap() = Some(child);
+
+    session.is_recording = true;
+    session.output_path = Some(url.clone());
+    session.start_time = Some(std::time::SystemTime::now());
+    session.ffmpeg_process = Some(pid);
+    session.encoder = encoder;
+    session.mode = RecordingMode::Record;
+
+    Ok(format!("Streaming started to {} (PID: {})", url, pid))
+}
+
+#[cfg(not(windows))]
+pub fn start_streaming_process(
+    _target: OutputTarget,
+    _requested_encoder: VideoEncoder,
+) -> Result<String, String> {
+    Err("Screen capture is only supported on Windows".to_string())
+}
+
+/// Stop a live stream started with `start_streaming_process`. Unlike file recording
+/// there is nothing to mux afterwards - FFmpeg has been writing directly to the
+/// ingest endpoint the whole time.
+pub fn stop_streaming_process() -> Result<String, String> {
+    let mut session = RECORDING_SESSION.lock().unwrap();
+    if !session.is_recording {
+        return Err("No recording in progress".to_string());
+    }
+
+    let url = session.output_path.clone().unwrap_or_default();
+    drop(session);
+
+    kill_ffmpeg_child();
+    let _ = super::audio_capture::stop_audio_capture_piped();
+
+    let mut session = RECORDING_SESSION.lock().unwrap();
+    session.is_recording = false;
+    session.output_path = None;
+    session.ffmpeg_process = None;
+
+    Ok(format!("Streaming stopped ({})", url))
+}
+
 pub fn stop_screen_recording_process() -> Result<String, String> {
     use std::io::Write;
     
@@ -282,7 +609,8 @@ pub fn stop_screen_recording_process() -> Result<String, String> {
     session.ffmpeg_process = None;
     session.audio_start_time = None;
     session.video_start_time = None;
-    
+    session.encoder = VideoEncoder::Libx264;
+
     // Release lock before running FFmpeg
     drop(session);
     
@@ -439,3 +767,278 @@ pub fn get_recording_status() -> Result<bool, String> {
     Ok(session.is_recording)
 }
 
+/// Detect scene cuts in a finished recording and split it into separate clips.
+/// Runs FFmpeg's `select='gt(scene,threshold)'` filter to collect the timestamps
+/// where the inter-frame difference exceeds `threshold`, merges cuts closer together
+/// than `min_clip_secs` so we don't emit tiny fragments, then `-c copy`s one segment
+/// per resulting interval into `output_dir`.
+pub fn split_into_scenes(
+    video_path: &str,
+    output_dir: &str,
+    threshold: f64,
+    min_clip_secs: f64,
+) -> Result<Vec<String>, String> {
+    let duration = get_video_duration(video_path)?;
+    let cuts = detect_scene_cuts(video_path, threshold)?;
+
+    let mut boundaries = vec![0.0];
+    boundaries.extend(cuts);
+    if *boundaries.last().unwrap() < duration {
+        boundaries.push(duration);
+    }
+
+    // Merge cuts that fall closer together than min_clip_secs to avoid tiny fragments.
+    let mut merged = vec![boundaries[0]];
+    for &b in &boundaries[1..] {
+        if b - *merged.last().unwrap() >= min_clip_secs {
+            merged.push(b);
+        }
+    }
+    if *merged.last().unwrap() < duration {
+        *merged.last_mut().unwrap() = duration;
+    }
+
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let mut clip_paths = Vec::new();
+    for (i, window) in merged.windows(2).enumerate() {
+        let (start, end) = (window[0], window[1]);
+        let clip_path = format!("{}/clip_{:03}.mp4", output_dir, i);
+
+        let output = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-ss").arg(format!("{:.3}", start))
+            .arg("-i").arg(video_path)
+            .arg("-t").arg(format!("{:.3}", end - start))
+            .arg("-c").arg("copy")
+            .arg(&clip_path)
+            .arg("-hide_banner")
+            .arg("-loglevel").arg("warning")
+            .output()
+            .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to split clip {}: {}", i, error));
+        }
+
+        clip_paths.push(clip_path);
+    }
+
+    Ok(clip_paths)
+}
+
+/// Run FFmpeg's scene filter over `video_path` and parse `showinfo`'s `pts_time:`
+/// fields for every frame selected as a scene change.
+fn detect_scene_cuts(video_path: &str, threshold: f64) -> Result<Vec<f64>, String> {
+    let output = Command::new("ffmpeg")
+        .arg("-i").arg(video_path)
+        .arg("-filter:v").arg(format!("select='gt(scene,{:.3})',showinfo", threshold))
+        .arg("-f").arg("null")
+        .arg("-")
+        .arg("-hide_banner")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg scene detection: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut cuts = Vec::new();
+    for line in stderr.lines() {
+        if let Some(idx) = line.find("pts_time:") {
+            let rest = &line[idx + "pts_time:".len()..];
+            if let Some(ts) = rest.split_whitespace().next() {
+                if let Ok(secs) = ts.parse::<f64>() {
+                    cuts.push(secs);
+                }
+            }
+        }
+    }
+    cuts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(cuts)
+}
+
+/// Start the instant-replay ring buffer: FFmpeg writes continuously into a segmented
+/// temp directory, wrapping after `buffer_seconds` worth of segments plus one slack
+/// segment (see `replay_segment_wrap`) so nothing is
+/// ever written permanently until `save_replay` is called.
+#[cfg(windows)]
+pub fn start_replay_buffer(buffer_seconds: u32) -> Result<String, String> {
+    let mut session = RECORDING_SESSION.lock().unwrap();
+
+    if session.is_recording {
+        return Err("Recording already in progress".to_string());
+    }
+
+    let buffer_dir = std::env::temp_dir().join(format!(
+        "vibeclips_replay_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+    std::fs::create_dir_all(&buffer_dir)
+        .map_err(|e| format!("Failed to create replay buffer directory: {}", e))?;
+
+    let segment_pattern = buffer_dir.join("segment_%05d.mp4");
+    let segment_wrap = replay_segment_wrap(buffer_seconds);
+    let segment_list = buffer_dir.join("segments.txt");
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.arg("-y");
+    cmd.arg("-f").arg("gdigrab");
+    cmd.arg("-draw_mouse").arg("0");
+    cmd.arg("-framerate").arg("30");
+    cmd.arg("-i").arg("desktop");
+    cmd.args(encoder_args(VideoEncoder::Libx264));
+    cmd.arg("-pix_fmt").arg("yuv420p");
+    cmd.arg("-f").arg("segment");
+    cmd.arg("-segment_time").arg("1");
+    cmd.arg("-segment_wrap").arg(segment_wrap.to_string());
+    cmd.arg("-segment_list").arg(segment_list.to_str().unwrap());
+    cmd.arg("-segment_list_type").arg("flat");
+    cmd.arg("-reset_timestamps").arg("1");
+    cmd.arg(segment_pattern.to_str().unwrap());
+    cmd.arg("-hide_banner");
+    cmd.arg("-loglevel").arg("warning");
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::piped());
+
+    let child = cmd.spawn()
+        .map_err(|e| format!("Failed to start FFmpeg: {}. Make sure FFmpeg is installed and in PATH.", e))?;
+    let pid = child.id();
+
+    let mut ffmpeg_child = FFMPEG_CHILD.lock().unwrap();
+    *ffmpeg_child = Some(child);
+
+    *REPLAY_BUFFER_DIR.lock().unwrap() = Some(buffer_dir);
+
+    session.is_recording = true;
+    session.output_path = None;
+    session.start_time = Some(std::time::SystemTime::now());
+    session.ffmpeg_process = Some(pid);
+    session.mode = RecordingMode::Replay;
+    session.replay_buffer_seconds = buffer_seconds;
+
+    Ok(format!("Replay buffer started (PID: {}, {}s window)", pid, buffer_seconds))
+}
+
+#[cfg(not(windows))]
+pub fn start_replay_buffer(_buffer_seconds: u32) -> Result<String, String> {
+    Err("Screen capture is only supported on Windows".to_string())
+}
+
+/// Stop the replay buffer and discard its rolling segments.
+pub fn stop_replay_buffer() -> Result<String, String> {
+    let mut session = RECORDING_SESSION.lock().unwrap();
+
+    if !session.is_recording || session.mode != RecordingMode::Replay {
+        return Err("No replay buffer in progress".to_string());
+    }
+
+    kill_ffmpeg_child();
+
+    session.is_recording = false;
+    session.output_path = None;
+    session.ffmpeg_process = None;
+    session.mode = RecordingMode::Record;
+    session.replay_buffer_seconds = 0;
+    drop(session);
+
+    if let Some(dir) = REPLAY_BUFFER_DIR.lock().unwrap().take() {
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    Ok("Replay buffer stopped".to_string())
+}
+
+/// Concatenate the most recent `seconds` worth of segments from the active replay
+/// buffer into `output_path`, without interrupting the ongoing capture.
+pub fn save_replay(output_path: String, seconds: u32) -> Result<String, String> {
+    let session = RECORDING_SESSION.lock().unwrap();
+    if !session.is_recording || session.mode != RecordingMode::Replay {
+        return Err("No replay buffer in progress".to_string());
+    }
+    drop(session);
+
+    let buffer_dir = REPLAY_BUFFER_DIR.lock().unwrap().clone()
+        .ok_or("Replay buffer directory not found")?;
+
+    // `segment_wrap` cyclically reuses `segment_%05d.mp4` filenames, so sorting by name
+    // no longer reflects recording order once the buffer has wrapped. FFmpeg's
+    // `-segment_list` is appended to in write order regardless of wrapping, so read
+    // recording order from there instead of from the filenames themselves.
+    let segment_list = buffer_dir.join("segments.txt");
+    let list_contents = std::fs::read_to_string(&segment_list)
+        .map_err(|e| format!("Failed to read replay segment list: {}", e))?;
+    let segments: Vec<std::path::PathBuf> = list_contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(std::path::PathBuf::from)
+        .filter(|p| p.exists())
+        .collect();
+
+    if segments.is_empty() {
+        return Err("Replay buffer has no segments yet".to_string());
+    }
+
+    // Each segment is ~1s; keep only the last `seconds` of them (plus the in-progress one).
+    let keep = (seconds as usize).max(1) + 1;
+    let recent: Vec<&std::path::PathBuf> = segments.iter().rev().take(keep).rev().collect();
+
+    let concat_file = buffer_dir.join("save_replay_concat.txt");
+    let concat_content: String = recent
+        .iter()
+        .map(|f| format!("file '{}'\n", f.to_str().unwrap()))
+        .collect();
+    std::fs::write(&concat_file, concat_content)
+        .map_err(|e| format!("Failed to write concat file: {}", e))?;
+
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-f").arg("concat")
+        .arg("-safe").arg("0")
+        .arg("-i").arg(concat_file.to_str().unwrap())
+        .arg("-c").arg("copy")
+        .arg(&output_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("warning")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    let _ = std::fs::remove_file(&concat_file);
+
+    if output.status.success() {
+        Ok(output_path)
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Failed to save replay: {}", error))
+    }
+}
+
+/// Send 'q' to the tracked FFmpeg child for a graceful stop, force-killing after a timeout.
+fn kill_ffmpeg_child() {
+    use std::io::Write;
+    use std::time::Duration;
+
+    let mut ffmpeg_child = FFMPEG_CHILD.lock().unwrap();
+    if let Some(mut child) = ffmpeg_child.take() {
+        if let Some(stdin) = child.stdin.as_mut() {
+            let _ = stdin.write_all(b"q");
+            let _ = stdin.flush();
+        }
+
+        for _ in 0..15 {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) => std::thread::sleep(Duration::from_millis(200)),
+                Err(_) => break,
+            }
+        }
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
@@ -0,0 +1,237 @@
+/// One user-marked fast-forward region of a clip: `start`/`end` are seconds
+/// on the clip's own trimmed timeline (0 = the clip's first frame after
+/// `trim_start` is applied, matching the same frame of reference FFmpeg's
+/// `-ss`/`-t` already put the clip in), and `factor` is the playback-speed
+/// multiplier applied to that region (e.g. 3.0 plays it 3x as fast).
+pub type SpeedSegment = (f64, f64, f64);
+
+/// Check that `segments` are well-formed against a clip trimmed to
+/// `[0, duration)`: every segment's bounds fall inside the clip, every
+/// factor is positive, and no two segments overlap - unlike a filter like
+/// brightness, a speed ramp changes the output's time axis, so an overlap
+/// would mean two different speeds claiming the same output frame.
+pub fn validate_segments(segments: &[SpeedSegment], duration: f64) -> Result<(), String> {
+    let mut sorted = segments.to_vec();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut cursor = 0.0;
+    for (start, end, factor) in &sorted {
+        if *start < 0.0 || *end > duration + f64::EPSILON || *start >= *end {
+            return Err(format!(
+                "Speed segment [{:.3}, {:.3}) is out of the clip's trimmed bounds [0, {:.3})",
+                start, end, duration
+            ));
+        }
+        if *factor <= 0.0 {
+            return Err(format!("Speed factor {} must be positive", factor));
+        }
+        if *start < cursor - f64::EPSILON {
+            return Err(format!(
+                "Speed segments overlap: a segment starting at {:.3} begins before the previous one ends at {:.3}",
+                start, cursor
+            ));
+        }
+        cursor = *end;
+    }
+    Ok(())
+}
+
+/// Decompose `factor` into a chain of `atempo=N` stages, each within
+/// FFmpeg's supported 0.5-2.0 range per stage (e.g. a 4.0x speedup becomes
+/// two chained `atempo=2.0` filters).
+pub fn atempo_chain(factor: f64) -> Vec<String> {
+    let mut remaining = factor;
+    let mut stages = Vec::new();
+    while remaining > 2.0 {
+        stages.push("atempo=2.0".to_string());
+        remaining /= 2.0;
+    }
+    while remaining < 0.5 {
+        stages.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    stages.push(format!("atempo={:.6}", remaining));
+    stages
+}
+
+/// Fill the gaps between `segments` with implicit factor-1.0 pieces so the
+/// result partitions `[0, duration)` completely, in order. Assumes
+/// `segments` has already passed `validate_segments`.
+fn full_timeline(segments: &[SpeedSegment], duration: f64) -> Vec<SpeedSegment> {
+    let mut sorted = segments.to_vec();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut pieces = Vec::new();
+    let mut cursor = 0.0;
+    for (start, end, factor) in sorted {
+        if start > cursor {
+            pieces.push((cursor, start, 1.0));
+        }
+        pieces.push((start, end, factor));
+        cursor = end;
+    }
+    if cursor < duration {
+        pieces.push((cursor, duration, 1.0));
+    }
+    pieces
+}
+
+/// Total output duration once every piece of the clip plays back at its own
+/// speed factor, for progress tracking and for remapping the timeline
+/// clips/subtitles after this one are positioned on.
+pub fn remapped_duration(segments: &[SpeedSegment], duration: f64) -> f64 {
+    if segments.is_empty() {
+        return duration;
+    }
+    full_timeline(segments, duration)
+        .iter()
+        .map(|(start, end, factor)| (end - start) / factor)
+        .sum()
+}
+
+/// Map a timestamp on the clip's original (pre-ramp) timeline to where it
+/// lands on the sped-up output timeline, by walking the same pieces
+/// `remapped_duration` sums over and compressing/expanding whichever piece
+/// `original_time` falls in. Used to keep burned-in subtitle cues in sync
+/// with clips that contain speed ramps.
+pub fn remap_time(original_time: f64, segments: &[SpeedSegment], duration: f64) -> f64 {
+    if segments.is_empty() {
+        return original_time;
+    }
+    let mut output_time = 0.0;
+    for (start, end, factor) in full_timeline(segments, duration) {
+        if original_time < end {
+            output_time += (original_time.max(start) - start) / factor;
+            return output_time;
+        }
+        output_time += (end - start) / factor;
+    }
+    output_time
+}
+
+/// Build the `filter_complex` fragment that retimes a `duration`-long clip's
+/// `segments` by trimming each piece of `full_timeline` and `setpts`/
+/// `atempo`-ing it to its own speed factor, then concatenating the pieces
+/// back into a single `[v_out_label]`/`[a_out_label]` pair - the same
+/// trim-then-concat idea a speed-ramp edit conceptually describes, built as
+/// one FFmpeg filter graph instead of separate encode passes. `video_in`/
+/// `audio_in` are FFmpeg stream specifiers (e.g. `"0:v"`/`"0:a"`).
+pub fn build_filter_complex(
+    video_in: &str,
+    audio_in: &str,
+    segments: &[SpeedSegment],
+    duration: f64,
+    v_out_label: &str,
+    a_out_label: &str,
+) -> String {
+    let pieces = full_timeline(segments, duration);
+    let mut filters = Vec::new();
+    let mut v_labels = Vec::new();
+    let mut a_labels = Vec::new();
+
+    for (i, (start, end, factor)) in pieces.iter().enumerate() {
+        let vlabel = format!("vseg{}", i);
+        let alabel = format!("aseg{}", i);
+
+        if (*factor - 1.0).abs() < f64::EPSILON {
+            filters.push(format!(
+                "[{video_in}]trim={start:.3}:{end:.3},setpts=PTS-STARTPTS[{vlabel}]"
+            ));
+        } else {
+            filters.push(format!(
+                "[{video_in}]trim={start:.3}:{end:.3},setpts=(PTS-STARTPTS)/{factor:.6}[{vlabel}]"
+            ));
+        }
+
+        let atempo = atempo_chain(*factor).join(",");
+        filters.push(format!(
+            "[{audio_in}]atrim={start:.3}:{end:.3},asetpts=PTS-STARTPTS,{atempo}[{alabel}]"
+        ));
+
+        v_labels.push(format!("[{vlabel}]"));
+        a_labels.push(format!("[{alabel}]"));
+    }
+
+    filters.push(format!(
+        "{}concat=n={}:v=1:a=0[{}]",
+        v_labels.join(""),
+        pieces.len(),
+        v_out_label,
+    ));
+    filters.push(format!(
+        "{}concat=n={}:v=0:a=1[{}]",
+        a_labels.join(""),
+        pieces.len(),
+        a_out_label,
+    ));
+
+    filters.join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atempo_chain_within_range() {
+        assert_eq!(atempo_chain(1.0), vec!["atempo=1.000000"]);
+        assert_eq!(atempo_chain(2.0), vec!["atempo=2.000000"]);
+        assert_eq!(atempo_chain(0.5), vec!["atempo=0.500000"]);
+    }
+
+    #[test]
+    fn test_atempo_chain_above_range() {
+        // 4.0x is out of FFmpeg's single-stage 0.5-2.0 range, so it splits
+        // into two atempo=2.0 stages (2.0 * 2.0 = 4.0).
+        let stages = atempo_chain(4.0);
+        assert_eq!(stages, vec!["atempo=2.0", "atempo=2.000000"]);
+    }
+
+    #[test]
+    fn test_atempo_chain_below_range() {
+        // 0.25x similarly splits into two atempo=0.5 stages.
+        let stages = atempo_chain(0.25);
+        assert_eq!(stages, vec!["atempo=0.5", "atempo=0.500000"]);
+    }
+
+    #[test]
+    fn test_remap_time_no_segments() {
+        assert_eq!(remap_time(5.0, &[], 10.0), 5.0);
+    }
+
+    #[test]
+    fn test_remap_time_with_speedup_segment() {
+        // [2, 6) plays at 2x, so that 4s span takes 2s on the output timeline.
+        let segments = vec![(2.0, 6.0, 2.0)];
+        assert_eq!(remap_time(0.0, &segments, 10.0), 0.0);
+        assert_eq!(remap_time(2.0, &segments, 10.0), 2.0);
+        assert_eq!(remap_time(4.0, &segments, 10.0), 3.0);
+        assert_eq!(remap_time(6.0, &segments, 10.0), 4.0);
+        assert_eq!(remap_time(8.0, &segments, 10.0), 6.0);
+    }
+
+    #[test]
+    fn test_remapped_duration_with_speedup_segment() {
+        let segments = vec![(2.0, 6.0, 2.0)];
+        // 0-2 at 1x (2s) + 2-6 at 2x (2s) + 6-10 at 1x (4s) = 8s.
+        assert_eq!(remapped_duration(&segments, 10.0), 8.0);
+    }
+
+    #[test]
+    fn test_validate_segments_rejects_overlap() {
+        let segments = vec![(0.0, 5.0, 2.0), (4.0, 8.0, 2.0)];
+        assert!(validate_segments(&segments, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_validate_segments_rejects_non_positive_factor() {
+        let segments = vec![(0.0, 5.0, 0.0)];
+        assert!(validate_segments(&segments, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_validate_segments_accepts_well_formed_segments() {
+        let segments = vec![(0.0, 5.0, 2.0), (5.0, 10.0, 0.5)];
+        assert!(validate_segments(&segments, 10.0).is_ok());
+    }
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// A single ABR rendition requested for an HLS export: resolution plus a target
+/// video bitrate (passed straight through to `-b:v`/`-maxrate`/`-bufsize`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamVariant {
+    pub width: u32,
+    pub height: u32,
+    pub bitrate: u32,
+}
+
+const DEFAULT_SEGMENT_SECS: f64 = 6.0;
+const DEFAULT_FPS: f64 = 30.0;
+
+/// Build a small default ABR ladder when the caller doesn't specify one:
+/// the source resolution plus up to two standard downscaled renditions (720p
+/// above 720 lines, 480p above 480 lines) at lower bitrates, so dropping an export straight onto a static host gives
+/// viewers on slow connections something to fall back to without requiring
+/// every caller to hand-pick a ladder.
+pub fn default_variant_ladder(source_width: u32, source_height: u32, source_bitrate: u32) -> Vec<StreamVariant> {
+    let mut variants = vec![StreamVariant {
+        width: source_width,
+        height: source_height,
+        bitrate: source_bitrate,
+    }];
+
+    if source_height > 720 {
+        variants.push(StreamVariant { width: 1280, height: 720, bitrate: 2800 });
+    }
+    if source_height > 480 {
+        variants.push(StreamVariant { width: 854, height: 480, bitrate: 1400 });
+    }
+
+    variants
+}
+
+/// Read the source's frame rate via `ffprobe` so segment boundaries can be
+/// aligned to a keyframe interval that actually matches the footage, instead
+/// of assuming a fixed 30fps. `r_frame_rate` comes back as a `num/den`
+/// fraction (e.g. `30000/1001`); falls back to `DEFAULT_FPS` if ffprobe can't
+/// be run or the source doesn't report one.
+fn probe_fps(ffprobe_path: &str, input_path: &str) -> f64 {
+    let output = Command::new(ffprobe_path)
+        .arg("-v").arg("error")
+        .arg("-select_streams").arg("v:0")
+        .arg("-show_entries").arg("stream=r_frame_rate")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input_path)
+        .output();
+
+    let Ok(output) = output else {
+        return DEFAULT_FPS;
+    };
+    if !output.status.success() {
+        return DEFAULT_FPS;
+    }
+
+    let raw = String::from_utf8_lossy(&output.stdout);
+    let raw = raw.trim();
+    match raw.split_once('/') {
+        Some((num, den)) => {
+            let num: f64 = num.parse().unwrap_or(DEFAULT_FPS);
+            let den: f64 = den.parse().unwrap_or(1.0);
+            if den > 0.0 { num / den } else { DEFAULT_FPS }
+        }
+        None => raw.parse().unwrap_or(DEFAULT_FPS),
+    }
+}
+
+/// Package `input_path` (an already-composited timeline) as fragmented-MP4 HLS:
+/// one rendition per entry in `variants`, each written to its own subdirectory as
+/// numbered `.m4s` segments plus a variant `.m3u8`, with a master playlist tying
+/// them together so a web player can switch between renditions. Returns the path
+/// to the master playlist.
+pub fn export_hls(
+    ffmpeg_path: &str,
+    ffprobe_path: &str,
+    input_path: &str,
+    output_dir: &str,
+    segment_secs: Option<f64>,
+    variants: &[StreamVariant],
+    preset: &str,
+) -> Result<String, String> {
+    if variants.is_empty() {
+        return Err("At least one variant is required for HLS export".to_string());
+    }
+
+    let segment_secs = segment_secs.unwrap_or(DEFAULT_SEGMENT_SECS);
+    let fps = probe_fps(ffprobe_path, input_path);
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create HLS output directory: {}", e))?;
+
+    for variant in variants {
+        encode_variant(ffmpeg_path, input_path, output_dir, variant, segment_secs, fps, preset)?;
+    }
+
+    write_master_playlist(output_dir, variants)
+}
+
+/// Resolution-tagged name shared by a variant's playlist and segment subdirectory,
+/// e.g. `720p`.
+fn variant_name(variant: &StreamVariant) -> String {
+    format!("{}p", variant.height)
+}
+
+/// Encode one rendition straight to fMP4 HLS segments plus its own variant
+/// playlist, in a subdirectory named after its resolution.
+fn encode_variant(
+    ffmpeg_path: &str,
+    input_path: &str,
+    output_dir: &str,
+    variant: &StreamVariant,
+    segment_secs: f64,
+    fps: f64,
+    preset: &str,
+) -> Result<(), String> {
+    let name = variant_name(variant);
+    let variant_dir = Path::new(output_dir).join(&name);
+    std::fs::create_dir_all(&variant_dir)
+        .map_err(|e| format!("Failed to create variant directory for {}: {}", name, e))?;
+
+    let playlist_path = variant_dir.join("stream.m3u8");
+    let segment_pattern = variant_dir.join("segment_%04d.m4s");
+    let init_segment = variant_dir.join("init.mp4");
+
+    let maxrate = variant.bitrate * 3 / 2;
+    let bufsize = variant.bitrate * 2;
+
+    let output = crate::hidden_command(ffmpeg_path)
+        .arg("-y")
+        .arg("-i").arg(input_path)
+        .arg("-vf").arg(format!("scale={}:{}", variant.width, variant.height))
+        .arg("-c:v").arg("libx264")
+        .arg("-preset").arg(preset)
+        .arg("-b:v").arg(format!("{}k", variant.bitrate))
+        .arg("-maxrate").arg(format!("{}k", maxrate))
+        .arg("-bufsize").arg(format!("{}k", bufsize))
+        .arg("-g").arg(((segment_secs * fps).round() as u32).to_string())
+        .arg("-sc_threshold").arg("0")
+        .arg("-c:a").arg("aac")
+        .arg("-b:a").arg("128k")
+        .arg("-f").arg("hls")
+        .arg("-hls_time").arg(segment_secs.to_string())
+        .arg("-hls_playlist_type").arg("vod")
+        .arg("-hls_segment_type").arg("fmp4")
+        .arg("-hls_fmp4_init_filename").arg(init_segment.file_name().unwrap())
+        .arg("-hls_segment_filename").arg(&segment_pattern)
+        .arg(&playlist_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg HLS encode for {}: {}", name, e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "HLS encode failed for variant {}: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+/// Write the top-level multivariant playlist referencing each rendition's own
+/// playlist, tagged with its `BANDWIDTH`/`RESOLUTION` so players can pick a
+/// rendition to start with and switch between them on the fly.
+fn write_master_playlist(output_dir: &str, variants: &[StreamVariant]) -> Result<String, String> {
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:7\n");
+
+    for variant in variants {
+        let name = variant_name(variant);
+        playlist.push_str(&format!(
+            "#EXT-X-STREAM-INF:BANDWIDTH={},RESOLUTION={}x{}\n{}/stream.m3u8\n",
+            variant.bitrate * 1000,
+            variant.width,
+            variant.height,
+            name,
+        ));
+    }
+
+    let master_path = Path::new(output_dir).join("master.m3u8");
+    std::fs::write(&master_path, playlist)
+        .map_err(|e| format!("Failed to write master playlist: {}", e))?;
+
+    Ok(master_path.to_string_lossy().to_string())
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// MP4/MOV delivery mode for `export_video`'s single combined-timeline
+/// export, independent of the video codec `output_codec` picks. `Progressive`
+/// is today's single `faststart` file; `FragmentedMp4` instead muxes the same
+/// file as a streaming-friendly fragmented MP4 (the container HTTP streaming
+/// players expect, as opposed to `streaming_export`'s separate multi-rendition
+/// HLS packaging). Defaults to `Progressive` when not specified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamingFormat {
+    #[default]
+    Progressive,
+    FragmentedMp4,
+}
+
+impl StreamingFormat {
+    /// `-movflags` value this mode needs. Fragmented delivery replaces the
+    /// single trailing `moov` atom `faststart` relies on with a `moof` per
+    /// fragment, so a player/CDN can start serving the file before the whole
+    /// thing has finished encoding.
+    pub fn movflags(&self) -> &'static str {
+        match self {
+            StreamingFormat::Progressive => "faststart",
+            StreamingFormat::FragmentedMp4 => "+frag_keyframe+empty_moov+default_base_moof",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StreamingFormat::Progressive => "progressive MP4",
+            StreamingFormat::FragmentedMp4 => "fragmented MP4",
+        }
+    }
+}
+
+/// Reject a fragmented-MP4 request against a container that can't carry it -
+/// fMP4 is an MP4/MOV muxer feature, and WebM (the export path's other
+/// supported container) has no equivalent here.
+pub fn validate_container(format: StreamingFormat, output_path: &str) -> Result<(), String> {
+    if format == StreamingFormat::Progressive {
+        return Ok(());
+    }
+    let ext = Path::new(output_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if ext == "mp4" || ext == "mov" {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} output requires an .mp4 or .mov path, got: '{}'",
+            format.label(),
+            output_path
+        ))
+    }
+}
+
+/// Remux a finished fragmented-MP4 `output_path` (stream copy, no re-encode)
+/// into a discrete init segment (`<stem>-init.mp4`) plus numbered media
+/// segments (`<stem>-seg%04d.m4s`) alongside it, for servers that hand
+/// clients individual segment files rather than one growing one. Returns the
+/// init segment path and the segment name pattern so the caller can report
+/// them back to the user.
+pub fn write_segments(
+    ffmpeg_path: &str,
+    output_path: &str,
+    segment_seconds: f64,
+) -> Result<(String, String), String> {
+    let stem = Path::new(output_path)
+        .with_extension("")
+        .to_string_lossy()
+        .to_string();
+    let init_path = format!("{}-init.mp4", stem);
+    let segment_pattern = format!("{}-seg%04d.m4s", stem);
+
+    let status = Command::new(ffmpeg_path)
+        .arg("-y")
+        .arg("-i").arg(output_path)
+        .arg("-map").arg("0")
+        .arg("-c").arg("copy")
+        .arg("-f").arg("segment")
+        .arg("-segment_format").arg("mp4")
+        .arg("-segment_format_options")
+        .arg("movflags=+frag_keyframe+empty_moov+default_base_moof")
+        .arg("-init_seg_name").arg(&init_path)
+        .arg("-segment_time").arg(format!("{:.3}", segment_seconds))
+        .arg("-reset_timestamps").arg("1")
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("quiet")
+        .arg(&segment_pattern)
+        .status()
+        .map_err(|e| format!("Failed to segment fragmented MP4: {}", e))?;
+
+    if status.success() {
+        Ok((init_path, segment_pattern))
+    } else {
+        Err(format!(
+            "Segmenting fragmented MP4 failed with exit code: {:?}",
+            status.code()
+        ))
+    }
+}
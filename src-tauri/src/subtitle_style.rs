@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use crate::transcription::{interpolate_word_timings, parse_srt};
+
+/// Visual style for burned-in ASS subtitles: colours, font, and outline, all
+/// independent of the karaoke word-highlight toggle (`prepare_subtitle_filter`'s
+/// `karaoke` flag), which only changes how the dialogue text itself is built.
+#[derive(Debug, Clone)]
+pub struct SubtitleStyle {
+    pub font_family: String,
+    pub font_size: u32,
+    /// ASS `&HAABBGGRR` hex colour of the base (not-yet-spoken) text.
+    pub primary_colour: String,
+    /// ASS `&HAABBGGRR` hex colour karaoke highlights the currently-spoken word in.
+    pub highlight_colour: String,
+    pub outline_colour: String,
+    pub back_colour: String,
+    pub outline_width: f32,
+    pub shadow_width: f32,
+    pub bold: bool,
+    /// Numpad-style ASS `Alignment` value (2 = bottom-centre).
+    pub alignment: u32,
+    pub margin_v: u32,
+}
+
+/// Named presets selectable from the export call, matching the frontend's
+/// style picker - see `StylePreset::style` for the concrete values each maps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StylePreset {
+    #[default]
+    Clean,
+    BoldPop,
+    Minimal,
+}
+
+impl StylePreset {
+    pub fn style(&self) -> SubtitleStyle {
+        match self {
+            StylePreset::Clean => SubtitleStyle {
+                font_family: "Arial".to_string(),
+                font_size: 42,
+                primary_colour: "&H00FFFFFF".to_string(),
+                highlight_colour: "&H0000D7FF".to_string(),
+                outline_colour: "&H00000000".to_string(),
+                back_colour: "&H00000000".to_string(),
+                outline_width: 2.0,
+                shadow_width: 1.0,
+                bold: false,
+                alignment: 2,
+                margin_v: 40,
+            },
+            StylePreset::BoldPop => SubtitleStyle {
+                font_family: "Arial Black".to_string(),
+                font_size: 54,
+                primary_colour: "&H00FFFFFF".to_string(),
+                highlight_colour: "&H0000FFFF".to_string(),
+                outline_colour: "&H00000000".to_string(),
+                back_colour: "&H00000000".to_string(),
+                outline_width: 4.0,
+                shadow_width: 0.0,
+                bold: true,
+                alignment: 2,
+                margin_v: 60,
+            },
+            StylePreset::Minimal => SubtitleStyle {
+                font_family: "Arial".to_string(),
+                font_size: 36,
+                primary_colour: "&H00FFFFFF".to_string(),
+                highlight_colour: "&H00FFFFFF".to_string(),
+                outline_colour: "&H00000000".to_string(),
+                back_colour: "&H00000000".to_string(),
+                outline_width: 1.0,
+                shadow_width: 0.0,
+                bold: false,
+                alignment: 2,
+                margin_v: 24,
+            },
+        }
+    }
+}
+
+/// Build the ASS `Style:` line (the 23-field format matching the `Format:`
+/// header `header()` writes) for this style, named `"Default"` so the
+/// `Dialogue:` lines below can reference it unchanged.
+fn style_line(style: &SubtitleStyle) -> String {
+    format!(
+        "Style: Default,{font},{size},{primary},{secondary},{outline},{back},{bold},0,0,0,100,100,0,0,1,{ow},{sw},{align},10,10,{mv},1\n",
+        font = style.font_family,
+        size = style.font_size,
+        primary = style.primary_colour,
+        secondary = style.highlight_colour,
+        outline = style.outline_colour,
+        back = style.back_colour,
+        bold = if style.bold { -1 } else { 0 },
+        ow = style.outline_width,
+        sw = style.shadow_width,
+        align = style.alignment,
+        mv = style.margin_v,
+    )
+}
+
+/// The `[Script Info]`/`[V4+ Styles]` header every `.ass` file needs before
+/// its `[Events]` dialogue lines, carrying `style`'s single `Default` style.
+fn header(style: &SubtitleStyle) -> String {
+    format!(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         Collisions: Normal\n\
+         PlayResX: 1920\n\
+         PlayResY: 1080\n\
+         ScaledBorderAndShadow: yes\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         {style_line}\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Text\n",
+        style_line = style_line(style),
+    )
+}
+
+/// Escape ASS's special characters (`\`, `{`, `}`) in subtitle text so stray
+/// backslashes or braces in a transcript can't be read as override tags.
+fn escape_ass_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('{', "\\{")
+        .replace('}', "\\}")
+        .replace('\n', "\\N")
+}
+
+/// Format a timestamp in seconds as ASS's `H:MM:SS.cc` (2-digit centiseconds).
+fn format_ass_time(seconds: f64) -> String {
+    let total_cs = (seconds.max(0.0) * 100.0).round() as u64;
+    let hours = total_cs / 360_000;
+    let minutes = (total_cs / 6_000) % 60;
+    let secs = (total_cs / 100) % 60;
+    let centis = total_cs % 100;
+    format!("{}:{:02}:{:02}.{:02}", hours, minutes, secs, centis)
+}
+
+/// Build a karaoke-tagged dialogue text: each word gets a `{\k<centiseconds>}`
+/// duration tag ahead of it, so libass reveals it in `SecondaryColour` (the
+/// style's `highlight_colour`) for exactly that long before moving to the next
+/// word - the classic ASS karaoke mechanism, no override tags beyond `\k` needed.
+fn build_karaoke_text(words: &[crate::transcription::WordTiming]) -> String {
+    let mut out = String::new();
+    for word in words {
+        let duration_cs = ((word.end_time - word.start_time).max(0.0) * 100.0).round() as u64;
+        out.push_str(&format!("{{\\k{}}}", duration_cs));
+        out.push_str(&escape_ass_text(word.text.trim()));
+        out.push(' ');
+    }
+    out.trim_end().to_string()
+}
+
+/// Render parsed SRT entries into full `.ass` file content under `style`. When
+/// `karaoke` is true, each entry's word-level timings (its own `words` when
+/// present - `parse_srt` never sets them, so SRT input always takes the other
+/// branch - otherwise interpolated across the entry's start/end window via
+/// `interpolate_word_timings`) drive a `\k`-tagged dialogue line instead of
+/// plain text.
+///
+/// `time_remap`, when given, is applied to every cue and word timestamp
+/// before it's written out - used when the clip(s) the subtitles are burned
+/// onto contain `speed_ramp` segments, so captions stay in sync with the
+/// compressed/expanded regions instead of the original (pre-ramp) timeline
+/// the SRT was authored against.
+pub fn build_ass_content(
+    srt_content: &str,
+    style: &SubtitleStyle,
+    karaoke: bool,
+    time_remap: Option<&dyn Fn(f64) -> f64>,
+) -> Result<String, String> {
+    let entries = parse_srt(srt_content)?;
+    let mut out = header(style);
+    let remap = |t: f64| time_remap.map_or(t, |f| f(t));
+
+    for entry in &entries {
+        let text = if karaoke {
+            let words = match &entry.words {
+                Some(words) => words.clone(),
+                None => interpolate_word_timings(&entry.text, entry.start_time, entry.end_time),
+            };
+            let remapped_words: Vec<crate::transcription::WordTiming> = words
+                .into_iter()
+                .map(|w| crate::transcription::WordTiming {
+                    start_time: remap(w.start_time),
+                    end_time: remap(w.end_time),
+                    ..w
+                })
+                .collect();
+            build_karaoke_text(&remapped_words)
+        } else {
+            escape_ass_text(&entry.text)
+        };
+
+        out.push_str(&format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{}\n",
+            format_ass_time(remap(entry.start_time)),
+            format_ass_time(remap(entry.end_time)),
+            text,
+        ));
+    }
+
+    Ok(out)
+}
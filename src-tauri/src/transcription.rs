@@ -7,6 +7,114 @@ pub struct SubtitleEntry {
     pub start_time: f64, // seconds
     pub end_time: f64,   // seconds
     pub text: String,
+    /// Per-word timings within this entry, for karaoke-style highlight-as-spoken
+    /// captions. Only populated by the `verbose_json` path; `None` for legacy SRT.
+    pub words: Option<Vec<WordTiming>>,
+}
+
+/// A single word's timing and confidence within a transcribed segment.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WordTiming {
+    pub text: String,
+    pub start_time: f64, // seconds
+    pub end_time: f64,   // seconds
+    pub confidence: f64, // 0.0-1.0
+}
+
+/// Raw shape of an OpenAI Whisper `verbose_json` response with word-level
+/// timestamp granularity requested.
+#[derive(Debug, Deserialize)]
+struct WhisperVerboseResponse {
+    segments: Vec<WhisperVerboseSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperVerboseSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    words: Option<Vec<WhisperVerboseWord>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperVerboseWord {
+    word: String,
+    start: f64,
+    end: f64,
+    #[serde(default)]
+    probability: Option<f64>,
+}
+
+/// A pluggable speech-to-text backend for the live audio-chunking caption mode.
+/// Implementations receive raw mono `f32` PCM for a single chunk and return the
+/// recognized text for it.
+pub trait Transcriber: Send + Sync {
+    fn transcribe(&self, pcm: &[f32], sample_rate: u32) -> Result<String, String>;
+}
+
+/// Default `Transcriber` backed by a local whisper.cpp `main`/`whisper-cli` binary,
+/// so live captioning works fully offline. Each chunk is written to a temp WAV file
+/// and passed to the binary with `-nt` (no timestamps) to get back plain text.
+pub struct WhisperCppTranscriber {
+    pub binary_path: String,
+    pub model_path: String,
+}
+
+impl WhisperCppTranscriber {
+    pub fn new(binary_path: impl Into<String>, model_path: impl Into<String>) -> Self {
+        Self {
+            binary_path: binary_path.into(),
+            model_path: model_path.into(),
+        }
+    }
+}
+
+impl Transcriber for WhisperCppTranscriber {
+    fn transcribe(&self, pcm: &[f32], sample_rate: u32) -> Result<String, String> {
+        use hound::{WavSpec, WavWriter};
+
+        let chunk_wav = std::env::temp_dir().join(format!(
+            "vibeclips_caption_chunk_{}.wav",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis()
+        ));
+
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&chunk_wav, spec)
+            .map_err(|e| format!("Failed to create caption chunk WAV: {}", e))?;
+        for &sample in pcm {
+            let sample_i16 = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            writer.write_sample(sample_i16)
+                .map_err(|e| format!("Failed to write caption chunk sample: {}", e))?;
+        }
+        writer.finalize()
+            .map_err(|e| format!("Failed to finalize caption chunk WAV: {}", e))?;
+
+        let output = Command::new(&self.binary_path)
+            .arg("-m").arg(&self.model_path)
+            .arg("-f").arg(&chunk_wav)
+            .arg("-nt") // no timestamps, we already know the chunk's window
+            .arg("-np") // no progress output
+            .output();
+
+        let _ = std::fs::remove_file(&chunk_wav);
+
+        let output = output.map_err(|e| format!("Failed to run whisper.cpp: {}", e))?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("whisper.cpp error: {}", error));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -97,8 +205,130 @@ pub async fn transcribe_audio_whisper(
     })
 }
 
+/// Transcribe audio using OpenAI Whisper API's `verbose_json` format with word-level
+/// timestamp granularity, for karaoke-style highlight-as-spoken captions.
+pub async fn transcribe_audio_whisper_verbose(
+    audio_path: &str,
+    api_key: &str,
+) -> Result<TranscriptionResponse, String> {
+    use std::fs::File;
+    use std::io::Read;
+
+    let mut file = File::open(audio_path)
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+
+    let mut audio_data = Vec::new();
+    file.read_to_end(&mut audio_data)
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+
+    let form = reqwest::multipart::Form::new()
+        .text("model", "whisper-1")
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "word")
+        .text("timestamp_granularities[]", "segment")
+        .part("file", reqwest::multipart::Part::bytes(audio_data)
+            .file_name("audio.mp3")
+            .mime_str("audio/mpeg")
+            .map_err(|e| format!("Failed to create multipart part: {}", e))?);
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post("https://api.openai.com/v1/audio/transcriptions")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("API error: {} - {}", status, error_text));
+    }
+
+    let body = response.text()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+
+    let subtitles = parse_whisper_verbose_json(&body)?;
+
+    Ok(TranscriptionResponse {
+        subtitles,
+        raw_srt: body,
+    })
+}
+
+/// Parse an OpenAI Whisper `verbose_json` response body into `SubtitleEntry`s with
+/// per-word timings. When a segment lacks `words` (granularity wasn't requested, or
+/// the backend omitted it), word boundaries are linearly interpolated across the
+/// segment's start/end based on each word's share of the segment's character count.
+fn parse_whisper_verbose_json(body: &str) -> Result<Vec<SubtitleEntry>, String> {
+    let parsed: WhisperVerboseResponse = serde_json::from_str(body)
+        .map_err(|e| format!("Failed to parse verbose_json response: {}", e))?;
+
+    let mut subtitles = Vec::new();
+    for (i, segment) in parsed.segments.iter().enumerate() {
+        let words = match &segment.words {
+            Some(words) if !words.is_empty() => words
+                .iter()
+                .map(|w| WordTiming {
+                    text: w.word.trim().to_string(),
+                    start_time: w.start,
+                    end_time: w.end,
+                    confidence: w.probability.unwrap_or(1.0),
+                })
+                .collect(),
+            _ => interpolate_word_timings(&segment.text, segment.start, segment.end),
+        };
+
+        subtitles.push(SubtitleEntry {
+            id: i as u32 + 1,
+            start_time: segment.start,
+            end_time: segment.end,
+            text: segment.text.trim().to_string(),
+            words: Some(words),
+        });
+    }
+
+    Ok(subtitles)
+}
+
+/// Spread a segment's words evenly across its start/end window, weighting each
+/// word's slice by its character count so longer words get proportionally more time.
+pub(crate) fn interpolate_word_timings(text: &str, start: f64, end: f64) -> Vec<WordTiming> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let total_chars: usize = words.iter().map(|w| w.len()).sum();
+    let duration = (end - start).max(0.0);
+    let mut cursor = start;
+
+    words
+        .iter()
+        .map(|&word| {
+            let share = if total_chars > 0 {
+                word.len() as f64 / total_chars as f64
+            } else {
+                1.0 / words.len() as f64
+            };
+            let word_duration = duration * share;
+            let word_start = cursor;
+            let word_end = word_start + word_duration;
+            cursor = word_end;
+            WordTiming {
+                text: word.to_string(),
+                start_time: word_start,
+                end_time: word_end,
+                confidence: 1.0,
+            }
+        })
+        .collect()
+}
+
 /// Parse SRT format subtitle file
-fn parse_srt(srt_content: &str) -> Result<Vec<SubtitleEntry>, String> {
+pub(crate) fn parse_srt(srt_content: &str) -> Result<Vec<SubtitleEntry>, String> {
     let mut subtitles = Vec::new();
     let blocks: Vec<&str> = srt_content.split("\n\n").collect();
     
@@ -126,6 +356,7 @@ fn parse_srt(srt_content: &str) -> Result<Vec<SubtitleEntry>, String> {
                 start_time,
                 end_time,
                 text,
+                words: None,
             });
         }
     }
@@ -194,6 +425,65 @@ mod tests {
         assert_eq!(end, 5.0);
     }
     
+    #[test]
+    fn test_interpolate_word_timings() {
+        let words = interpolate_word_timings("a bb ccc", 0.0, 6.0);
+        assert_eq!(words.len(), 3);
+        // 1 + 2 + 3 = 6 total chars over a 6s window, so each char gets 1s.
+        assert_eq!(words[0].text, "a");
+        assert_eq!(words[0].start_time, 0.0);
+        assert_eq!(words[0].end_time, 1.0);
+        assert_eq!(words[1].start_time, 1.0);
+        assert_eq!(words[1].end_time, 3.0);
+        assert_eq!(words[2].start_time, 3.0);
+        assert_eq!(words[2].end_time, 6.0);
+    }
+
+    #[test]
+    fn test_interpolate_word_timings_empty_text() {
+        assert!(interpolate_word_timings("   ", 0.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn test_parse_whisper_verbose_json_with_words() {
+        let body = r#"{
+            "segments": [
+                {
+                    "start": 0.0,
+                    "end": 2.0,
+                    "text": "hello world",
+                    "words": [
+                        {"word": " hello", "start": 0.0, "end": 1.0, "probability": 0.9},
+                        {"word": " world", "start": 1.0, "end": 2.0, "probability": 0.8}
+                    ]
+                }
+            ]
+        }"#;
+
+        let subtitles = parse_whisper_verbose_json(body).unwrap();
+        assert_eq!(subtitles.len(), 1);
+        let words = subtitles[0].words.as_ref().unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "hello");
+        assert_eq!(words[0].confidence, 0.9);
+        assert_eq!(words[1].text, "world");
+    }
+
+    #[test]
+    fn test_parse_whisper_verbose_json_falls_back_to_interpolation() {
+        let body = r#"{
+            "segments": [
+                {"start": 0.0, "end": 2.0, "text": "hello world"}
+            ]
+        }"#;
+
+        let subtitles = parse_whisper_verbose_json(body).unwrap();
+        let words = subtitles[0].words.as_ref().unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "hello");
+        assert_eq!(words[1].text, "world");
+    }
+
     #[test]
     fn test_parse_srt() {
         let srt_content = r#"1
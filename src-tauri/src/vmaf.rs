@@ -0,0 +1,333 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Result of a target-quality CRF search: the CRF value that was chosen and the
+/// VMAF score it actually measured, so the UI can show what was picked.
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetQualityResult {
+    pub crf: u32,
+    pub vmaf_score: f64,
+}
+
+const CRF_MIN: u32 = 15;
+const CRF_MAX: u32 = 40;
+const VMAF_TOLERANCE: f64 = 0.5;
+const PROBE_DURATION_SECS: f64 = 8.0;
+
+/// Check whether the located FFmpeg binary was built with `--enable-libvmaf`, so
+/// callers can fail fast with a clear error instead of a confusing filter error
+/// partway through an export.
+pub fn supports_libvmaf(ffmpeg_path: &str) -> bool {
+    let output = Command::new(ffmpeg_path).arg("-filters").output();
+    match output {
+        Ok(out) => String::from_utf8_lossy(&out.stdout).contains("libvmaf"),
+        Err(_) => false,
+    }
+}
+
+/// Run a bounded binary search over CRF range [`CRF_MIN`, `CRF_MAX`] on a short probe
+/// encoded from `input_path`, measuring VMAF against the source with FFmpeg's
+/// `libvmaf` filter after each trial encode, until the measured score is within
+/// `VMAF_TOLERANCE` of `target_vmaf` or the search interval collapses.
+pub fn find_crf_for_target_vmaf(
+    ffmpeg_path: &str,
+    input_path: &str,
+    target_vmaf: f64,
+    width: u32,
+    height: u32,
+    preset: &str,
+) -> Result<TargetQualityResult, String> {
+    if !supports_libvmaf(ffmpeg_path) {
+        return Err(
+            "This FFmpeg build doesn't support libvmaf; target-quality mode requires \
+             an FFmpeg built with --enable-libvmaf."
+                .to_string(),
+        );
+    }
+
+    let probe_source = make_probe_clip(ffmpeg_path, input_path)?;
+
+    let mut low = CRF_MIN;
+    let mut high = CRF_MAX;
+    let mut best: Option<TargetQualityResult> = None;
+
+    while low <= high {
+        let mid = low + (high - low) / 2;
+        let score = measure_vmaf_at_crf(ffmpeg_path, &probe_source, mid, width, height, preset)?;
+
+        let is_closer = match &best {
+            Some(b) => (score - target_vmaf).abs() < (b.vmaf_score - target_vmaf).abs(),
+            None => true,
+        };
+        if is_closer {
+            best = Some(TargetQualityResult { crf: mid, vmaf_score: score });
+        }
+
+        if (score - target_vmaf).abs() <= VMAF_TOLERANCE {
+            break;
+        }
+
+        // Lower CRF = higher quality/higher VMAF. If we're above target, we can
+        // afford a higher (more compressed) CRF; if below, we need a lower one.
+        if score > target_vmaf {
+            if mid == CRF_MAX {
+                break;
+            }
+            low = mid + 1;
+        } else {
+            if mid == CRF_MIN {
+                break;
+            }
+            high = mid - 1;
+        }
+    }
+
+    let _ = std::fs::remove_file(&probe_source);
+
+    best.ok_or_else(|| "Target-quality search produced no measurable VMAF score".to_string())
+}
+
+/// Cut a short representative sample near the start of the source to probe against,
+/// so the CRF search doesn't have to re-encode the whole clip at every trial.
+fn make_probe_clip(ffmpeg_path: &str, input_path: &str) -> Result<String, String> {
+    let probe_path = std::env::temp_dir().join(format!(
+        "vibeclips_vmaf_probe_src_{}.mp4",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+
+    let output = Command::new(ffmpeg_path)
+        .arg("-y")
+        .arg("-i").arg(input_path)
+        .arg("-t").arg(PROBE_DURATION_SECS.to_string())
+        .arg("-c").arg("copy")
+        .arg(&probe_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract VMAF probe clip: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(probe_path.to_string_lossy().to_string())
+}
+
+/// Encode `probe_source` at `crf`, then score the trial encode against
+/// `probe_source` (as the reference) with `libvmaf`, returning the pooled mean score.
+fn measure_vmaf_at_crf(
+    ffmpeg_path: &str,
+    probe_source: &str,
+    crf: u32,
+    width: u32,
+    height: u32,
+    preset: &str,
+) -> Result<f64, String> {
+    let trial_path = std::env::temp_dir().join(format!(
+        "vibeclips_vmaf_trial_{}.mp4",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+
+    let mut encode_cmd = Command::new(ffmpeg_path);
+    encode_cmd.arg("-y").arg("-i").arg(probe_source);
+    if width > 0 && height > 0 {
+        encode_cmd.arg("-vf").arg(format!("scale={}:{}", width, height));
+    }
+    encode_cmd
+        .arg("-c:v").arg("libx264")
+        .arg("-preset").arg(preset)
+        .arg("-crf").arg(crf.to_string())
+        .arg("-an")
+        .arg(&trial_path)
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error");
+
+    let output = encode_cmd.output().map_err(|e| format!("Failed to run FFmpeg: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to encode VMAF trial at CRF {}: {}",
+            crf,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let log_path = std::env::temp_dir().join(format!(
+        "vibeclips_vmaf_log_{}.json",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    ));
+
+    // The trial was encoded at `width`x`height` (or probe_source's native resolution if
+    // unset); the reference must match it exactly or libvmaf rejects the pair. Scale the
+    // reference rather than the already-correctly-sized trial.
+    let filter_complex = if width > 0 && height > 0 {
+        format!(
+            "[1:v]scale={}:{}[ref];[0:v][ref]libvmaf=log_path={}:log_fmt=json",
+            width, height,
+            log_path.to_string_lossy().replace('\\', "/")
+        )
+    } else {
+        format!(
+            "[0:v][1:v]libvmaf=log_path={}:log_fmt=json",
+            log_path.to_string_lossy().replace('\\', "/")
+        )
+    };
+
+    let vmaf_output = Command::new(ffmpeg_path)
+        .arg("-i").arg(&trial_path)
+        .arg("-i").arg(probe_source)
+        .arg("-filter_complex")
+        .arg(filter_complex)
+        .arg("-f").arg("null")
+        .arg("-")
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg libvmaf: {}", e))?;
+
+    let _ = std::fs::remove_file(&trial_path);
+
+    if !vmaf_output.status.success() {
+        let _ = std::fs::remove_file(&log_path);
+        return Err(format!(
+            "libvmaf measurement failed: {}",
+            String::from_utf8_lossy(&vmaf_output.stderr)
+        ));
+    }
+
+    let score = parse_pooled_vmaf(&log_path);
+    let _ = std::fs::remove_file(&log_path);
+    score
+}
+
+/// Resolves a per-clip CRF against a shared target VMAF, probing each distinct
+/// source file at most once - multiple clips trimmed from the same source
+/// (a common case when a user cuts one recording into several timeline clips)
+/// share a single probe result instead of re-running the binary search.
+/// When no target VMAF was requested, every lookup returns `fallback_crf`
+/// untouched and never probes anything.
+pub struct CrfCache {
+    target_vmaf: Option<f64>,
+    fallback_crf: String,
+    width: u32,
+    height: u32,
+    preset: String,
+    cache: Mutex<HashMap<String, String>>,
+}
+
+impl CrfCache {
+    pub fn new(target_vmaf: Option<f64>, fallback_crf: String, width: u32, height: u32, preset: String) -> Self {
+        CrfCache {
+            target_vmaf,
+            fallback_crf,
+            width,
+            height,
+            preset,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolve the CRF to use for `source_path`. Returns the chosen CRF and,
+    /// only on a fresh probe (not a cache hit), the VMAF score it measured -
+    /// callers can log that for visibility into what target-quality picked.
+    pub fn crf_for(&self, ffmpeg_path: &str, source_path: &str) -> Result<(String, Option<f64>), String> {
+        let Some(target) = self.target_vmaf else {
+            return Ok((self.fallback_crf.clone(), None));
+        };
+
+        if let Some(cached) = self.cache.lock().unwrap().get(source_path) {
+            return Ok((cached.clone(), None));
+        }
+
+        // Target-quality mode needs an FFmpeg built with libvmaf; rather than
+        // failing the whole export over it, fall back to the user's fixed CRF
+        // for this (and every other) clip, same as when no target was set.
+        if !supports_libvmaf(ffmpeg_path) {
+            println!(
+                "WARNING: FFmpeg build lacks libvmaf; using fixed CRF {} instead of target VMAF {}",
+                self.fallback_crf, target
+            );
+            self.cache.lock().unwrap().insert(source_path.to_string(), self.fallback_crf.clone());
+            return Ok((self.fallback_crf.clone(), None));
+        }
+
+        let result = find_crf_for_target_vmaf(
+            ffmpeg_path,
+            source_path,
+            target,
+            self.width,
+            self.height,
+            &self.preset,
+        )?;
+        let crf_str = result.crf.to_string();
+        self.cache.lock().unwrap().insert(source_path.to_string(), crf_str.clone());
+        Ok((crf_str, Some(result.vmaf_score)))
+    }
+}
+
+/// Parse the pooled mean VMAF score out of libvmaf's JSON log
+/// (`.pooled_metrics.vmaf.mean`).
+fn parse_pooled_vmaf(log_path: &std::path::Path) -> Result<f64, String> {
+    let content = std::fs::read_to_string(log_path)
+        .map_err(|e| format!("Failed to read VMAF log: {}", e))?;
+    let parsed: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse VMAF log: {}", e))?;
+
+    parsed
+        .get("pooled_metrics")
+        .and_then(|m| m.get("vmaf"))
+        .and_then(|v| v.get("mean"))
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| "VMAF log missing pooled_metrics.vmaf.mean".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp_log(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_pooled_vmaf() {
+        let path = write_temp_log(
+            "vibeclips_test_vmaf_log_ok.json",
+            r#"{"pooled_metrics": {"vmaf": {"mean": 95.432}}}"#,
+        );
+        assert_eq!(parse_pooled_vmaf(&path).unwrap(), 95.432);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_parse_pooled_vmaf_missing_field() {
+        let path = write_temp_log(
+            "vibeclips_test_vmaf_log_missing.json",
+            r#"{"pooled_metrics": {"psnr": {"mean": 40.0}}}"#,
+        );
+        assert!(parse_pooled_vmaf(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_parse_pooled_vmaf_invalid_json() {
+        let path = write_temp_log("vibeclips_test_vmaf_log_bad.json", "not json");
+        assert!(parse_pooled_vmaf(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}